@@ -0,0 +1,70 @@
+// Adaptive per-service concurrency
+//
+// With `--adaptive-concurrency`, every AWS call holds a slot of its service's
+// AIMD limit while it runs. The limit grows while calls succeed and halves when
+// the service throttles, so each service settles near the fastest rate it
+// accepts instead of a fixed `--concurrency`. The parallel helpers that fan
+// out calls size their batches from the same limit, so a throttled service
+// also gets fewer calls queued for a slot.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use crate::utils::parallel::{AimdLimit, AimdPermit};
+
+/// Calls in flight per service when a run starts
+const INITIAL_LIMIT: usize = 4;
+/// Upper bound of calls in flight per service
+const MAX_LIMIT: usize = 64;
+
+/// AIMD limits of every service called during a run
+#[derive(Default)]
+pub struct AdaptiveConcurrency {
+    limits: Mutex<BTreeMap<String, Arc<AimdLimit>>>,
+}
+
+/// Limit reached by a service at the end of a run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceLimit {
+    pub service: String,
+    /// Calls allowed in flight
+    pub limit: usize,
+    /// Times the limit was halved after throttling
+    pub decreases: u64,
+}
+
+impl AdaptiveConcurrency {
+    /// Wait for a slot to call `service`
+    pub async fn acquire(&self, service: &str) -> AimdPermit {
+        self.limit(service).acquire().await
+    }
+
+    /// Calls currently allowed in flight for `service`
+    pub fn current(&self, service: &str) -> usize {
+        self.limit(service).current()
+    }
+
+    fn limit(&self, service: &str) -> Arc<AimdLimit> {
+        Arc::clone(
+            self.limits
+                .lock()
+                .unwrap()
+                .entry(service.to_string())
+                .or_insert_with(|| Arc::new(AimdLimit::new(INITIAL_LIMIT, 1, MAX_LIMIT))),
+        )
+    }
+
+    /// Limits chosen per service, sorted by service
+    pub fn summary(&self) -> Vec<ServiceLimit> {
+        self.limits
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(service, limit)| ServiceLimit {
+                service: service.clone(),
+                limit: limit.current(),
+                decreases: limit.decreases(),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,289 @@
+// Amazon Resource Names
+//
+// `arn:{partition}:{service}:{region}:{account}:{resource}`, where the
+// resource is `type/id`, `type:id` or a bare id depending on the service.
+// Collectors prefer the ARN a payload reports; `Arn::for_resource` builds it
+// for resource types whose APIs only return an identifier.
+
+use anyhow::Result;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arn {
+    /// `aws`, `aws-cn`, `aws-us-gov`...
+    pub partition: String,
+    /// Service namespace, e.g. `ec2` or `elasticloadbalancing`
+    pub service: String,
+    /// Empty for global resources
+    pub region: String,
+    /// Empty for resources without an owner account, e.g. S3 buckets
+    pub account: String,
+    pub resource: String,
+}
+
+impl Arn {
+    pub fn new(
+        partition: &str,
+        service: &str,
+        region: &str,
+        account: &str,
+        resource: &str,
+    ) -> Self {
+        Self {
+            partition: partition.to_string(),
+            service: service.to_string(),
+            region: region.to_string(),
+            account: account.to_string(),
+            resource: resource.to_string(),
+        }
+    }
+
+    /// Resource type, e.g. `instance` for `instance/i-0abc` or `function` for
+    /// `function:name`; `None` for bare ids such as S3 bucket names
+    pub fn resource_type(&self) -> Option<&str> {
+        self.split_resource().0
+    }
+
+    /// Resource id, e.g. `i-0abc` for `instance/i-0abc`
+    pub fn resource_id(&self) -> &str {
+        self.split_resource().1
+    }
+
+    fn split_resource(&self) -> (Option<&str>, &str) {
+        match self.resource.find(['/', ':']) {
+            Some(at) => (Some(&self.resource[..at]), &self.resource[at + 1..]),
+            None => (None, &self.resource),
+        }
+    }
+
+    /// ARN of a collected resource whose payload does not report one
+    ///
+    /// `partition` is the partition of the collected account, which global
+    /// resources cannot be told from their region. `service` and
+    /// `resource_type` are the collector's names; `region` is the collection
+    /// region (`global` for global services). Returns `None` for resource types
+    /// that have no ARN or whose ARN cannot be derived from `id`.
+    pub fn for_resource(
+        partition: &str,
+        service: &str,
+        resource_type: &str,
+        region: &str,
+        account: &str,
+        id: &str,
+    ) -> Option<Self> {
+        let region = if region == "global" { "" } else { region };
+        let regional = |namespace: &str, resource: String| {
+            Self::new(partition, namespace, region, account, &resource)
+        };
+        let global = |namespace: &str, account: &str, resource: String| {
+            Self::new(partition, namespace, "", account, &resource)
+        };
+
+        let arn = match (service, resource_type) {
+            // EC2 and VPC
+            ("ec2", "images") => Self::new(partition, "ec2", region, "", &format!("image/{}", id)),
+            ("ec2", "snapshots") => {
+                Self::new(partition, "ec2", region, "", &format!("snapshot/{}", id))
+            }
+            ("ec2" | "vpc", resource_type) => {
+                let kind = match resource_type {
+                    "instances" => "instance",
+                    "vpcs" => "vpc",
+                    "subnets" => "subnet",
+                    "route-tables" => "route-table",
+                    "internet-gateways" => "internet-gateway",
+                    "nat-gateways" => "natgateway",
+                    "network-acls" => "network-acl",
+                    "security-groups" => "security-group",
+                    "vpc-endpoints" => "vpc-endpoint",
+                    "elastic-ips" => "elastic-ip",
+                    "volumes" => "volume",
+                    "network-interfaces" => "network-interface",
+                    "launch-templates" => "launch-template",
+                    "vpc-peering-connections" => "vpc-peering-connection",
+                    "transit-gateway-attachments" => "transit-gateway-attachment",
+                    "vpn-connections" => "vpn-connection",
+                    "vpn-gateways" => "vpn-gateway",
+                    "customer-gateways" => "customer-gateway",
+                    _ => return None,
+                };
+                regional("ec2", format!("{}/{}", kind, id))
+            }
+
+            // Global services
+            ("s3", "buckets") => global("s3", "", id.to_string()),
+            ("cloudfront", resource_type) => {
+                let kind = match resource_type {
+                    "distributions" => "distribution",
+                    "origin-access-identities" => "origin-access-identity",
+                    "cache-policies" => "cache-policy",
+                    "origin-request-policies" => "origin-request-policy",
+                    "response-headers-policies" => "response-headers-policy",
+                    "functions" => "function",
+                    _ => return None,
+                };
+                global("cloudfront", account, format!("{}/{}", kind, id))
+            }
+            ("route53", "hosted-zones") => global(
+                "route53",
+                "",
+                format!("hostedzone/{}", id.trim_start_matches("/hostedzone/")),
+            ),
+            ("route53", "health-checks") => global("route53", "", format!("healthcheck/{}", id)),
+            ("route53", "traffic-policies") => {
+                global("route53", "", format!("trafficpolicy/{}", id))
+            }
+
+            // Regional services
+            ("cloudwatch", "insights-rules") => {
+                regional("cloudwatch", format!("insight-rule/{}", id))
+            }
+            ("ecr", "repositories") => regional("ecr", format!("repository/{}", id)),
+            ("ecs", "clusters") => regional("ecs", format!("cluster/{}", id)),
+            ("elasticache", "cache-clusters") => regional("elasticache", format!("cluster:{}", id)),
+            ("elasticache", "replication-groups") => {
+                regional("elasticache", format!("replicationgroup:{}", id))
+            }
+            ("elb", "classic-load-balancers") => {
+                regional("elasticloadbalancing", format!("loadbalancer/{}", id))
+            }
+            ("eventbridge", "event-buses") => regional("events", format!("event-bus/{}", id)),
+            ("eventbridge", "archives") => regional("events", format!("archive/{}", id)),
+            ("eventbridge", "replays") => regional("events", format!("replay/{}", id)),
+            ("lambda", "functions") => regional("lambda", format!("function:{}", id)),
+            ("rds", "db-instances") => regional("rds", format!("db:{}", id)),
+            ("rds", "db-clusters") => regional("rds", format!("cluster:{}", id)),
+            ("ses", resource_type) => {
+                let kind = match resource_type {
+                    "identities" => "identity",
+                    "configuration-sets" => "configuration-set",
+                    "receipt-rule-sets" => "receipt-rule-set",
+                    "templates" => "template",
+                    "custom-verification-email-templates" => "custom-verification-email-template",
+                    _ => return None,
+                };
+                regional("ses", format!("{}/{}", kind, id))
+            }
+            // Queues are identified by URL: https://sqs.{region}.amazonaws.com/{account}/{name}
+            ("sqs", "queues") => regional("sqs", id.rsplit('/').next()?.to_string()),
+            _ => return None,
+        };
+
+        Some(arn)
+    }
+}
+
+impl FromStr for Arn {
+    type Err = anyhow::Error;
+
+    fn from_str(arn: &str) -> Result<Self> {
+        // The resource may itself contain `:`, e.g. `function:name:alias`
+        let parts: Vec<&str> = arn.splitn(6, ':').collect();
+        let [prefix, partition, service, region, account, resource] = parts[..] else {
+            anyhow::bail!("Invalid ARN '{}': expected 6 ':'-separated parts", arn);
+        };
+        if prefix != "arn" || partition.is_empty() || service.is_empty() || resource.is_empty() {
+            anyhow::bail!("Invalid ARN '{}'", arn);
+        }
+
+        Ok(Self::new(partition, service, region, account, resource))
+    }
+}
+
+impl fmt::Display for Arn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "arn:{}:{}:{}:{}:{}",
+            self.partition, self.service, self.region, self.account, self.resource
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_splits_resource_type_and_id() {
+        let arn: Arn = "arn:aws-cn:lambda:cn-north-1:123456789012:function:api:live"
+            .parse()
+            .unwrap();
+        assert_eq!(arn.partition, "aws-cn");
+        assert_eq!(arn.account, "123456789012");
+        assert_eq!(arn.resource_type(), Some("function"));
+        assert_eq!(arn.resource_id(), "api:live");
+        assert_eq!(
+            arn.to_string(),
+            "arn:aws-cn:lambda:cn-north-1:123456789012:function:api:live"
+        );
+
+        let bucket: Arn = "arn:aws:s3:::logs".parse().unwrap();
+        assert_eq!(bucket.resource_type(), None);
+        assert_eq!(bucket.resource_id(), "logs");
+
+        assert!("arn:aws:s3".parse::<Arn>().is_err());
+        assert!("urn:aws:s3:::logs".parse::<Arn>().is_err());
+    }
+
+    #[test]
+    fn test_for_resource_builds_service_arns() {
+        let arn = |service, resource_type, region, id| {
+            let partition = match region {
+                "us-gov-west-1" => "aws-us-gov",
+                _ => "aws",
+            };
+            Arn::for_resource(
+                partition,
+                service,
+                resource_type,
+                region,
+                "123456789012",
+                id,
+            )
+            .map(|arn| arn.to_string())
+        };
+
+        assert_eq!(
+            arn("ec2", "instances", "us-gov-west-1", "i-0abc").as_deref(),
+            Some("arn:aws-us-gov:ec2:us-gov-west-1:123456789012:instance/i-0abc")
+        );
+        assert_eq!(
+            arn("cloudfront", "distributions", "global", "E2ABC").as_deref(),
+            Some("arn:aws:cloudfront::123456789012:distribution/E2ABC")
+        );
+        assert_eq!(
+            Arn::for_resource(
+                "aws-cn",
+                "cloudfront",
+                "distributions",
+                "global",
+                "123456789012",
+                "E2ABC"
+            )
+            .map(|arn| arn.to_string())
+            .as_deref(),
+            Some("arn:aws-cn:cloudfront::123456789012:distribution/E2ABC")
+        );
+        assert_eq!(
+            arn("route53", "hosted-zones", "global", "/hostedzone/Z123").as_deref(),
+            Some("arn:aws:route53:::hostedzone/Z123")
+        );
+        assert_eq!(
+            arn("s3", "buckets", "us-east-1", "logs").as_deref(),
+            Some("arn:aws:s3:::logs")
+        );
+        assert_eq!(
+            arn(
+                "sqs",
+                "queues",
+                "eu-west-1",
+                "https://sqs.eu-west-1.amazonaws.com/123456789012/jobs"
+            )
+            .as_deref(),
+            Some("arn:aws:sqs:eu-west-1:123456789012:jobs")
+        );
+        assert_eq!(arn("cloudformation", "exports", "us-east-1", "x"), None);
+    }
+}
@@ -0,0 +1,155 @@
+// Audit log of AWS API calls
+//
+// Every call made through `AwsCli::execute` is appended as one JSON line to the
+// audit file, giving an exact record of the read APIs a collection invoked and
+// how long each one took.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::error::AwsErrorKind;
+
+/// One AWS API call, including its retries
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditRecord {
+    /// When the first attempt started (RFC 3339)
+    pub started_at: String,
+    pub profile: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role_arn: Option<String>,
+    /// Service collector that issued the call, if any
+    pub collector: Option<String>,
+    /// CLI service name, e.g. `s3api`
+    pub service: String,
+    /// CLI operation name, e.g. `get-bucket-policy`
+    pub operation: String,
+    /// Region the call ran in: its `--region`, else the default region of the
+    /// role session or profile; `None` if that could not be resolved
+    pub region: Option<String>,
+    /// Wall time of all attempts, including backoff
+    pub duration_ms: u64,
+    /// Exit code of the last attempt; `None` if the CLI could not be run
+    pub exit_code: Option<i32>,
+    pub retries: u32,
+    /// Class of the final error, `None` for successful calls
+    pub error_class: Option<AwsErrorKind>,
+}
+
+/// JSONL audit file shared by every client of a run
+pub struct AuditLog {
+    path: PathBuf,
+    writer: Mutex<BufWriter<File>>,
+    records: Mutex<Vec<AuditRecord>>,
+}
+
+impl AuditLog {
+    /// Create (or truncate) the audit file
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .context(format!("Failed to create directory {}", dir.display()))?;
+        }
+        let file =
+            File::create(path).context(format!("Failed to create audit log {}", path.display()))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: Mutex::new(BufWriter::new(file)),
+            records: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a call to the audit file
+    ///
+    /// The audit log must not fail a collection, so write errors are only
+    /// reported.
+    pub fn record(&self, record: AuditRecord) {
+        let written = serde_json::to_string(&record)
+            .map_err(anyhow::Error::from)
+            .and_then(|line| {
+                writeln!(self.writer.lock().unwrap(), "{}", line).map_err(anyhow::Error::from)
+            });
+        if let Err(e) = written {
+            eprintln!(
+                "⚠️  Failed to write audit log {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+
+        self.records.lock().unwrap().push(record);
+    }
+
+    /// Flush buffered records to the audit file
+    pub fn flush(&self) -> Result<()> {
+        self.writer
+            .lock()
+            .unwrap()
+            .flush()
+            .context(format!("Failed to write audit log {}", self.path.display()))
+    }
+
+    /// Number of recorded calls
+    pub fn len(&self) -> usize {
+        self.records.lock().unwrap().len()
+    }
+
+    /// The `n` calls that took the longest, slowest first
+    pub fn slowest(&self, n: usize) -> Vec<AuditRecord> {
+        let mut records = self.records.lock().unwrap().clone();
+        records.sort_by_key(|record| std::cmp::Reverse(record.duration_ms));
+        records.truncate(n);
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(operation: &str, duration_ms: u64) -> AuditRecord {
+        AuditRecord {
+            started_at: "2026-01-18T04:12:01+00:00".to_string(),
+            profile: "default".to_string(),
+            role_arn: None,
+            collector: Some("ec2".to_string()),
+            service: "ec2".to_string(),
+            operation: operation.to_string(),
+            region: Some("us-east-1".to_string()),
+            duration_ms,
+            exit_code: Some(0),
+            retries: 0,
+            error_class: None,
+        }
+    }
+
+    #[test]
+    fn test_records_are_written_as_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let audit = AuditLog::create(&path).unwrap();
+
+        audit.record(record("describe-vpcs", 120));
+        audit.record(record("describe-instances", 900));
+        audit.flush().unwrap();
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["operation"], "describe-vpcs");
+        assert_eq!(lines[0]["error_class"], serde_json::Value::Null);
+
+        assert_eq!(audit.slowest(1)[0].operation, "describe-instances");
+    }
+}
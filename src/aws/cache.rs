@@ -0,0 +1,52 @@
+// Run-scoped response cache
+//
+// Several collectors issue the same read calls (ECS lists its clusters for
+// every resource type, EC2 and VPC both describe the VPCs). Successful
+// responses are cached for the duration of a run, keyed by the profile, the
+// assumed role and the full argument list (which carries `--region`), and
+// concurrent identical calls share a single in-flight invocation.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+/// Identity of a call: profile, assumed role and arguments
+pub type CacheKey = (String, Option<String>, Vec<String>);
+
+#[derive(Default)]
+pub struct ResponseCache {
+    cells: Mutex<HashMap<CacheKey, Arc<OnceCell<Value>>>>,
+    requests: AtomicU64,
+    calls: AtomicU64,
+}
+
+impl ResponseCache {
+    /// Response for `key`, running `call` only if no identical call has
+    /// succeeded or is in flight
+    ///
+    /// Failures are not cached: a caller waiting on a failed call makes its own
+    /// attempt.
+    pub async fn get_or_call<F, Fut>(&self, key: CacheKey, call: F) -> anyhow::Result<Value>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<Value>>,
+    {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        let cell = Arc::clone(self.cells.lock().unwrap().entry(key).or_default());
+
+        cell.get_or_try_init(|| {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            call()
+        })
+        .await
+        .cloned()
+    }
+
+    /// Number of requests answered without a new call
+    pub fn hits(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed) - self.calls.load(Ordering::Relaxed)
+    }
+}
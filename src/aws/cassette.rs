@@ -0,0 +1,386 @@
+// Record and replay of AWS CLI invocations
+//
+// A cassette is one JSON file per distinct command holding the arguments,
+// stdout, stderr, exit code and duration of the original call. Recording wraps
+// another executor and writes a cassette after every call; replaying serves
+// the stored results without touching the network.
+//
+// Credentials in responses (`sts assume-role`, `configure export-credentials`)
+// are replaced with placeholders before a cassette is written, so cassettes
+// can be shared and committed.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::executor::{CliOutput, CliRequest, CommandExecutor};
+
+/// Credential fields replaced in recorded responses
+const SECRET_FIELDS: [&str; 3] = ["AccessKeyId", "SecretAccessKey", "SessionToken"];
+
+/// Value recorded instead of a credential
+const REDACTED: &str = "REDACTED";
+
+/// Replace credentials anywhere in a response with placeholders
+///
+/// A credential set is an object with a `SecretAccessKey`, so access key IDs
+/// listed by `iam list-access-keys` stay intact.
+fn redact_credentials(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            if object.contains_key("SecretAccessKey") {
+                for field in SECRET_FIELDS {
+                    if let Some(secret) = object.get_mut(field) {
+                        *secret = Value::String(REDACTED.to_string());
+                    }
+                }
+            }
+            object.values_mut().for_each(redact_credentials);
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_credentials),
+        _ => {}
+    }
+}
+
+/// Stdout with credentials redacted; non-JSON output is kept as is
+fn redact_stdout(stdout: &str) -> String {
+    match serde_json::from_str::<Value>(stdout) {
+        Ok(mut value) => {
+            redact_credentials(&mut value);
+            value.to_string()
+        }
+        Err(_) => stdout.to_string(),
+    }
+}
+
+/// A recorded AWS CLI invocation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cassette {
+    pub profile: String,
+    /// Role the command ran under, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role_arn: Option<String>,
+    pub args: Vec<String>,
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+    pub recorded_at: String,
+}
+
+impl Cassette {
+    /// File name for the cassette of a command, stable across runs
+    fn file_name(profile: &str, role_arn: Option<&str>, args: &[String]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(profile.as_bytes());
+        hasher.update([0u8]);
+        if let Some(role_arn) = role_arn {
+            hasher.update(role_arn.as_bytes());
+            hasher.update([0u8]);
+        }
+        for arg in args {
+            hasher.update(arg.as_bytes());
+            hasher.update([0u8]);
+        }
+        let digest: String = hasher
+            .finalize()
+            .iter()
+            .take(8)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+
+        // Prefix with service and operation so the directory is browsable
+        let prefix: Vec<String> = args
+            .iter()
+            .take(2)
+            .map(|arg| {
+                arg.chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                    .collect()
+            })
+            .collect();
+
+        format!("{}_{}.json", prefix.join("_"), digest)
+    }
+}
+
+/// Executor that records every invocation of an inner executor to a directory
+pub struct RecordingExecutor {
+    inner: Arc<dyn CommandExecutor>,
+    dir: PathBuf,
+}
+
+impl RecordingExecutor {
+    pub fn new(inner: Arc<dyn CommandExecutor>, dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .context(format!("Failed to create cassette directory: {:?}", dir))?;
+
+        Ok(Self { inner, dir })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for RecordingExecutor {
+    async fn run(&self, request: &CliRequest) -> Result<CliOutput> {
+        let start = Instant::now();
+        let output = self.inner.run(request).await?;
+
+        let cassette = Cassette {
+            profile: request.profile.clone(),
+            role_arn: role_arn(request),
+            args: request.args.clone(),
+            exit_code: output.exit_code,
+            stdout: redact_stdout(&output.stdout),
+            stderr: output.stderr.clone(),
+            duration_ms: start.elapsed().as_millis() as u64,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let path = self.dir.join(Cassette::file_name(
+            &request.profile,
+            cassette.role_arn.as_deref(),
+            &request.args,
+        ));
+        let json =
+            serde_json::to_string_pretty(&cassette).context("Failed to serialize cassette")?;
+        tokio::fs::write(&path, json)
+            .await
+            .context(format!("Failed to write cassette: {:?}", path))?;
+
+        Ok(output)
+    }
+}
+
+/// Role ARN a request runs under, part of the cassette key
+fn role_arn(request: &CliRequest) -> Option<String> {
+    request.role.as_ref().map(|role| role.role_arn.clone())
+}
+
+/// Profile, role ARN and arguments identifying a recorded command
+type CassetteKey = (String, Option<String>, Vec<String>);
+
+/// Executor that serves previously recorded cassettes, fully offline
+pub struct ReplayExecutor {
+    cassettes: HashMap<CassetteKey, Cassette>,
+}
+
+impl ReplayExecutor {
+    /// Load every cassette in `dir`
+    ///
+    /// Credentials in cassettes recorded before redaction are replaced with
+    /// placeholders as well.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut cassettes = HashMap::new();
+
+        let entries =
+            fs::read_dir(dir).context(format!("Failed to read cassette directory: {:?}", dir))?;
+
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+
+            let content = fs::read_to_string(&path)
+                .context(format!("Failed to read cassette: {:?}", path))?;
+            let mut cassette: Cassette = serde_json::from_str(&content)
+                .context(format!("Failed to parse cassette: {:?}", path))?;
+            cassette.stdout = redact_stdout(&cassette.stdout);
+            cassettes.insert(
+                (
+                    cassette.profile.clone(),
+                    cassette.role_arn.clone(),
+                    cassette.args.clone(),
+                ),
+                cassette,
+            );
+        }
+
+        Ok(Self { cassettes })
+    }
+
+    /// Number of loaded cassettes
+    pub fn len(&self) -> usize {
+        self.cassettes.len()
+    }
+
+    /// Whether no cassettes were found
+    pub fn is_empty(&self) -> bool {
+        self.cassettes.is_empty()
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for ReplayExecutor {
+    async fn run(&self, request: &CliRequest) -> Result<CliOutput> {
+        let cassette = self
+            .cassettes
+            .get(&(
+                request.profile.clone(),
+                role_arn(request),
+                request.args.clone(),
+            ))
+            .ok_or_else(|| {
+                anyhow!(
+                    "No recorded cassette for profile '{}': aws {}",
+                    request.profile,
+                    request.args.join(" ")
+                )
+            })?;
+
+        Ok(CliOutput {
+            exit_code: cassette.exit_code,
+            stdout: cassette.stdout.clone(),
+            stderr: cassette.stderr.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::executor::FakeExecutor;
+    use serde_json::json;
+
+    fn request(args: &[&str]) -> CliRequest {
+        profile_request("default", args)
+    }
+
+    fn profile_request(profile: &str, args: &[&str]) -> CliRequest {
+        CliRequest {
+            profile: profile.to_string(),
+            role: None,
+            args: args.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Answers every command with the name of the profile it ran under
+    struct ProfileEcho;
+
+    #[async_trait]
+    impl CommandExecutor for ProfileEcho {
+        async fn run(&self, request: &CliRequest) -> Result<CliOutput> {
+            Ok(CliOutput {
+                exit_code: 0,
+                stdout: json!({ "Profile": request.profile }).to_string(),
+                stderr: String::new(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recorded_calls_replay_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        let fake = FakeExecutor::new()
+            .respond(&["ec2", "describe-vpcs"], json!({ "Vpcs": [] }))
+            .fail(&["iam", "list-users"], "An error occurred (AccessDenied)");
+
+        let recorder = RecordingExecutor::new(Arc::new(fake), dir.path()).unwrap();
+        recorder
+            .run(&request(&["ec2", "describe-vpcs"]))
+            .await
+            .unwrap();
+        recorder
+            .run(&request(&["iam", "list-users"]))
+            .await
+            .unwrap();
+
+        let replay = ReplayExecutor::load(dir.path()).unwrap();
+        assert_eq!(replay.len(), 2);
+
+        let vpcs = replay
+            .run(&request(&["ec2", "describe-vpcs"]))
+            .await
+            .unwrap();
+        assert!(vpcs.success());
+        assert_eq!(vpcs.stdout, json!({ "Vpcs": [] }).to_string());
+
+        let users = replay.run(&request(&["iam", "list-users"])).await.unwrap();
+        assert_eq!(users.exit_code, 254);
+        assert!(users.stderr.contains("AccessDenied"));
+
+        assert!(replay
+            .run(&request(&["s3api", "list-buckets"]))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_same_command_replayed_per_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = ["sts", "get-caller-identity"];
+
+        let recorder = RecordingExecutor::new(Arc::new(ProfileEcho), dir.path()).unwrap();
+        for profile in ["dev", "prod"] {
+            recorder
+                .run(&profile_request(profile, &args))
+                .await
+                .unwrap();
+        }
+
+        let replay = ReplayExecutor::load(dir.path()).unwrap();
+        assert_eq!(replay.len(), 2);
+
+        for profile in ["dev", "prod"] {
+            let output = replay.run(&profile_request(profile, &args)).await.unwrap();
+            assert_eq!(output.stdout, json!({ "Profile": profile }).to_string());
+        }
+        assert!(replay
+            .run(&profile_request("staging", &args))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recorded_credentials_redacted() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = [
+            "sts",
+            "assume-role",
+            "--role-arn",
+            "arn:aws:iam::111111111111:role/Audit",
+            "--role-session-name",
+            "cloud-collector",
+        ];
+        let fake = FakeExecutor::new().respond(
+            &args,
+            json!({ "Credentials": {
+                "AccessKeyId": "ASIASECRETKEYID",
+                "SecretAccessKey": "secret-access-key",
+                "SessionToken": "session-token",
+                "Expiration": "2026-01-01T00:00:00+00:00",
+            }}),
+        );
+
+        let recorder = RecordingExecutor::new(Arc::new(fake), dir.path()).unwrap();
+        let live = recorder.run(&request(&args)).await.unwrap();
+        assert!(live.stdout.contains("secret-access-key"));
+
+        for entry in fs::read_dir(dir.path()).unwrap() {
+            let content = fs::read_to_string(entry.unwrap().path()).unwrap();
+            for secret in ["ASIASECRETKEYID", "secret-access-key", "session-token"] {
+                assert!(!content.contains(secret), "cassette contains {}", secret);
+            }
+        }
+
+        let replayed = ReplayExecutor::load(dir.path())
+            .unwrap()
+            .run(&request(&args))
+            .await
+            .unwrap();
+        let credentials: Value = serde_json::from_str(&replayed.stdout).unwrap();
+        assert_eq!(credentials["Credentials"]["SecretAccessKey"], REDACTED);
+        assert_eq!(
+            credentials["Credentials"]["Expiration"],
+            "2026-01-01T00:00:00+00:00"
+        );
+    }
+}
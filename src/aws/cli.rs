@@ -1,50 +1,365 @@
 // AWS CLI command executor
 use anyhow::{Context, Result};
 use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::process::Command;
 
+use super::adaptive::AdaptiveConcurrency;
+use super::arn::Arn;
+use super::audit::{AuditLog, AuditRecord};
+use super::cache::ResponseCache;
+use super::endpoints::Endpoints;
+use super::error::{AwsError, AwsErrorKind};
+use super::executor::{AssumedRole, CliOutput, CliRequest, CommandExecutor};
+use super::rate_limit::{RateLimiter, RateLimits};
+use super::regions::DEFAULT_REGION;
+use super::retry::{self, FailureClass, RetryPolicy, RetryStats, RetrySummary};
+use super::sigv4::Credentials;
+
+/// An assumed role whose credentials are renewed before they expire, so that
+/// accounts collected late in a long run do not fail with `ExpiredToken`
+struct RoleSession {
+    role_arn: String,
+    region: String,
+    /// Client of the profile the role is assumed from
+    source: AwsCli,
+    current: tokio::sync::Mutex<Arc<AssumedRole>>,
+}
+
+impl RoleSession {
+    /// Current credentials, renewed when they are about to expire
+    async fn credentials(&self) -> Result<Arc<AssumedRole>> {
+        let mut current = self.current.lock().await;
+        if current.credentials.expiring() {
+            let renewed = Box::pin(self.source.request_role(&self.role_arn, &self.region)).await?;
+            *current = Arc::new(renewed);
+        }
+        Ok(Arc::clone(&current))
+    }
+}
+
 #[derive(Clone)]
 pub struct AwsCli {
     profile: String,
+    executor: Arc<dyn CommandExecutor>,
+    retry_policy: RetryPolicy,
+    retry_stats: Arc<RetryStats>,
+    role: Option<Arc<RoleSession>>,
+    /// `--duration-seconds` of assumed-role sessions; the role's default if unset
+    role_duration: Option<u64>,
+    /// The profile's default region, resolved on first use
+    default_region: Arc<tokio::sync::OnceCell<String>>,
+    endpoints: Arc<Endpoints>,
+    audit: Option<Arc<AuditLog>>,
+    /// Collector the client issues calls for, recorded in the audit log
+    collector: Option<String>,
+    /// Time after which a single invocation is abandoned and its process killed
+    call_timeout: Option<Duration>,
+    rate_limiter: Arc<RateLimiter>,
+    adaptive: Option<Arc<AdaptiveConcurrency>>,
+    cache: Option<Arc<ResponseCache>>,
 }
 
 impl AwsCli {
-    pub fn new(profile: String) -> Self {
-        Self { profile }
+    // Create a client that delegates every invocation to a custom executor
+    pub fn with_executor(profile: String, executor: Arc<dyn CommandExecutor>) -> Self {
+        Self {
+            profile,
+            executor,
+            retry_policy: RetryPolicy::default(),
+            retry_stats: Arc::new(RetryStats::default()),
+            role: None,
+            role_duration: None,
+            default_region: Arc::default(),
+            endpoints: Arc::new(Endpoints::default()),
+            audit: None,
+            collector: None,
+            call_timeout: None,
+            rate_limiter: Arc::new(RateLimiter::default()),
+            adaptive: None,
+            cache: None,
+        }
     }
 
-    // Execute AWS CLI command and return JSON output
-    pub async fn execute(&self, args: &[&str]) -> Result<Value> {
-        let mut cmd = Command::new("aws");
+    // Set the retry policy used for throttled and transient failures
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
 
-        // Disable pager to prevent hanging on large outputs
-        cmd.env("AWS_PAGER", "");
+    // Request assumed-role sessions lasting `seconds`
+    pub fn with_role_duration(mut self, seconds: u64) -> Self {
+        self.role_duration = Some(seconds);
+        self
+    }
 
-        // Add profile
-        cmd.arg("--profile").arg(&self.profile);
+    // Send commands to custom service endpoints (AWS emulators)
+    pub fn with_endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = Arc::new(endpoints);
+        self
+    }
 
-        // Add output format
-        cmd.arg("--output").arg("json");
+    // Abandon invocations that run longer than `timeout`
+    pub fn with_call_timeout(mut self, timeout: Duration) -> Self {
+        self.call_timeout = Some(timeout);
+        self
+    }
 
-        // Add no-cli-pager flag
-        cmd.arg("--no-cli-pager");
+    // Throttle calls client-side; the limiter is shared by all clones
+    pub fn with_rate_limits(mut self, limits: RateLimits) -> Self {
+        self.rate_limiter = Arc::new(RateLimiter::new(limits));
+        self
+    }
 
-        // Add user arguments
-        cmd.args(args);
+    // Limit calls in flight per service with limits that adapt to throttling
+    pub fn with_adaptive_concurrency(mut self, adaptive: Arc<AdaptiveConcurrency>) -> Self {
+        self.adaptive = Some(adaptive);
+        self
+    }
 
-        let output = cmd
-            .output()
+    // Serve identical calls of a run from a shared response cache
+    pub fn with_response_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    // Record every call made through `execute` in an audit log
+    pub fn with_audit_log(mut self, audit: Arc<AuditLog>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    // Client whose calls are attributed to a collector in the audit log
+    pub fn for_collector(&self, collector: &str) -> AwsCli {
+        Self {
+            collector: Some(collector.to_string()),
+            ..self.clone()
+        }
+    }
+
+    // Client for another profile sharing this client's executor, retry policy
+    // and retry counters
+    pub fn for_profile(&self, profile: &str) -> AwsCli {
+        Self {
+            profile: profile.to_string(),
+            role: None,
+            default_region: Arc::default(),
+            ..self.clone()
+        }
+    }
+
+    // Name of the profile commands run under
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    // Calls to run at once when fanning out over `service`: `configured`,
+    // capped by the service's current adaptive limit when there is one
+    pub fn concurrency_for(&self, service: &str, configured: usize) -> usize {
+        match &self.adaptive {
+            Some(adaptive) => configured.min(adaptive.current(service)).max(1),
+            None => configured,
+        }
+    }
+
+    // Retry counters accumulated by this client and all of its clones
+    pub fn retry_summary(&self) -> RetrySummary {
+        self.retry_stats.summary()
+    }
+
+    // Assume an IAM role and return a client whose commands run under it
+    //
+    // `region` is used for commands without `--region`, since the assumed
+    // credentials carry no profile configuration. The credentials are renewed
+    // shortly before they expire.
+    pub async fn assume_role(&self, role_arn: &str, region: &str) -> Result<AwsCli> {
+        let source = Self {
+            role: None,
+            ..self.clone()
+        };
+        let role = source.request_role(role_arn, region).await?;
+
+        Ok(Self {
+            role: Some(Arc::new(RoleSession {
+                role_arn: role_arn.to_string(),
+                region: region.to_string(),
+                source,
+                current: tokio::sync::Mutex::new(Arc::new(role)),
+            })),
+            ..self.clone()
+        })
+    }
+
+    // Call `sts assume-role`; never served from the response cache, so that a
+    // renewal gets new credentials
+    async fn request_role(&self, role_arn: &str, region: &str) -> Result<AssumedRole> {
+        let duration = self.role_duration.map(|seconds| seconds.to_string());
+        let mut args = vec![
+            "sts",
+            "assume-role",
+            "--role-arn",
+            role_arn,
+            "--role-session-name",
+            "cloud-collector",
+        ];
+        if let Some(duration) = &duration {
+            args.extend(["--duration-seconds", duration]);
+        }
+
+        let response = self
+            .execute_uncached(&args)
             .await
-            .context("Failed to execute AWS CLI command")?;
+            .context(format!("Failed to assume role {}", role_arn))?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("AWS CLI command failed: {}", stderr);
+        let credentials: Credentials = serde_json::from_value(response["Credentials"].clone())
+            .context("Failed to parse assumed role credentials")?;
+
+        Ok(AssumedRole {
+            role_arn: role_arn.to_string(),
+            credentials,
+            region: region.to_string(),
+        })
+    }
+
+    // ARN of the assumed role, if any
+    pub fn role_arn(&self) -> Option<&str> {
+        self.role.as_ref().map(|role| role.role_arn.as_str())
+    }
+
+    // ARN of the identity the credentials belong to; its partition tells
+    // commercial, GovCloud and China accounts apart
+    pub async fn caller_arn(&self) -> Result<Arn> {
+        let identity = self.execute(&["sts", "get-caller-identity"]).await?;
+        identity["Arn"]
+            .as_str()
+            .context("Caller identity has no ARN")?
+            .parse()
+    }
+
+    // Run a command through the executor and return its raw output
+    async fn run(&self, args: &[&str]) -> Result<CliOutput> {
+        // `configure` only reads local settings and makes no API call
+        if let [service, operation, ..] = args {
+            if *service != "configure" {
+                self.rate_limiter.acquire(service, operation).await;
+            }
+        }
+
+        let mut args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        if let Some(endpoint) = args
+            .first()
+            .and_then(|service| self.endpoints.for_service(service))
+        {
+            if !args.iter().any(|arg| arg == "--endpoint-url") {
+                args.extend(["--endpoint-url".to_string(), endpoint.to_string()]);
+            }
+        }
+
+        let role = match &self.role {
+            Some(session) => Some(session.credentials().await?),
+            None => None,
+        };
+        let request = CliRequest {
+            profile: self.profile.clone(),
+            role,
+            args,
+        };
+
+        let Some(timeout) = self.call_timeout else {
+            return self.executor.run(&request).await;
+        };
+
+        // Dropping the timed-out future kills the child process
+        match tokio::time::timeout(timeout, self.executor.run(&request)).await {
+            Ok(output) => output,
+            Err(_) => {
+                Err(AwsError::timed_out(&format!("aws {}", request.args.join(" ")), timeout).into())
+            }
+        }
+    }
+
+    // Execute AWS CLI command and return JSON output
+    //
+    // Throttled and transient failures are retried with jittered exponential
+    // backoff until the retry policy's attempt budget is spent. With a response
+    // cache, identical calls of the run are only made once.
+    pub async fn execute(&self, args: &[&str]) -> Result<Value> {
+        let Some(cache) = &self.cache else {
+            return self.execute_uncached(args).await;
+        };
+
+        let key = (
+            self.profile.clone(),
+            self.role_arn().map(String::from),
+            args.iter().map(|s| s.to_string()).collect(),
+        );
+        cache.get_or_call(key, || self.execute_uncached(args)).await
+    }
+
+    async fn execute_uncached(&self, args: &[&str]) -> Result<Value> {
+        let started_at = chrono::Utc::now();
+        let start = std::time::Instant::now();
+        let mut exit_code = None;
+        let mut attempt = 1;
+        let result = loop {
+            let permit = match &self.adaptive {
+                Some(adaptive) => Some(adaptive.acquire(args.first().unwrap_or(&"")).await),
+                None => None,
+            };
+            let output = match self.run(args).await {
+                Ok(output) => output,
+                Err(e) => break Err(e),
+            };
+            exit_code = Some(output.exit_code);
+            if output.success() {
+                if let Some(permit) = permit {
+                    permit.success();
+                }
+                break Ok(output);
+            }
+
+            let error = AwsError::from_output(&output);
+            let class = retry::classify_failure(&error);
+            // Release the slot before backing off; throttling shrinks the limit
+            if let Some(permit) = permit {
+                if class == FailureClass::Throttled {
+                    permit.overloaded();
+                }
+            }
+            if class == FailureClass::Permanent {
+                break Err(error.with_attempts(attempt).into());
+            }
+
+            if attempt >= self.retry_policy.max_attempts {
+                self.retry_stats.record_exhausted(class);
+                break Err(error.with_attempts(attempt).into());
+            }
+
+            self.retry_stats.record_retry(class);
+            tokio::time::sleep(self.retry_policy.backoff(attempt)).await;
+            attempt += 1;
+        };
+
+        if let Some(audit) = &self.audit {
+            audit.record(AuditRecord {
+                started_at: started_at.to_rfc3339(),
+                profile: self.profile.clone(),
+                role_arn: self.role_arn().map(String::from),
+                collector: self.collector.clone(),
+                service: args.first().unwrap_or(&"").to_string(),
+                operation: args.get(1).unwrap_or(&"").to_string(),
+                region: self.effective_region(args).await,
+                duration_ms: start.elapsed().as_millis() as u64,
+                exit_code,
+                retries: attempt - 1,
+                error_class: result.as_ref().err().map(AwsErrorKind::of),
+            });
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let output = result?;
         let json: Value =
-            serde_json::from_str(&stdout).context("Failed to parse AWS CLI JSON output")?;
+            serde_json::from_str(&output.stdout).context("Failed to parse AWS CLI JSON output")?;
 
         Ok(json)
     }
@@ -66,80 +381,373 @@ impl AwsCli {
 
     // Get default region for the profile
     pub async fn get_default_region(&self) -> Result<String> {
-        let output = Command::new("aws")
-            .arg("configure")
-            .arg("get")
-            .arg("region")
-            .arg("--profile")
-            .arg(&self.profile)
-            .output()
-            .await
-            .context("Failed to get default region from AWS profile")?;
+        self.default_region
+            .get_or_try_init(|| async {
+                let output = self
+                    .run(&["configure", "get", "region"])
+                    .await
+                    .context("Failed to get default region from AWS profile")?;
 
-        if !output.status.success() {
-            // If no region configured, default to ap-southeast-1
-            return Ok("ap-southeast-1".to_string());
-        }
+                if !output.success() {
+                    // If no region configured, use the default region
+                    return Ok(DEFAULT_REGION.to_string());
+                }
 
-        let region = String::from_utf8(output.stdout)
-            .context("Failed to parse region output")?
-            .trim()
-            .to_string();
+                let region = output.stdout.trim().to_string();
 
-        if region.is_empty() {
-            // If empty, default to ap-southeast-1
-            Ok("ap-southeast-1".to_string())
-        } else {
-            Ok(region)
+                if region.is_empty() {
+                    // If empty, use the default region
+                    Ok(DEFAULT_REGION.to_string())
+                } else {
+                    Ok(region)
+                }
+            })
+            .await
+            .cloned()
+    }
+
+    // Region a command runs in: its `--region`, else the default region of the
+    // role session or the profile
+    async fn effective_region(&self, args: &[&str]) -> Option<String> {
+        let explicit = args
+            .iter()
+            .position(|arg| *arg == "--region")
+            .and_then(|i| args.get(i + 1));
+        match (explicit, &self.role) {
+            (Some(region), _) => Some(region.to_string()),
+            (None, Some(session)) => Some(session.region.clone()),
+            (None, None) => self.get_default_region().await.ok(),
         }
     }
 
     // Validate AWS credentials by making a simple API call
     pub async fn validate_credentials(&self) -> Result<()> {
-        let output = Command::new("aws")
-            .env("AWS_PAGER", "")
-            .arg("--profile")
-            .arg(&self.profile)
-            .arg("--output")
-            .arg("json")
-            .arg("sts")
-            .arg("get-caller-identity")
-            .output()
+        let output = self
+            .run(&["sts", "get-caller-identity"])
             .await
             .context("Failed to validate AWS credentials")?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error = AwsError::from_output(&output);
 
-            // Check for common credential errors
-            if stderr.contains("InvalidToken") || stderr.contains("malformed") {
-                anyhow::bail!(
-                    "AWS credentials are invalid or expired for profile '{}'. Please refresh your credentials.\nError: {}",
-                    self.profile,
-                    stderr.trim()
-                );
-            } else if stderr.contains("could not be found") || stderr.contains("NoCredentialsError")
-            {
-                anyhow::bail!(
-                    "No credentials found for profile '{}'. Please configure your AWS credentials.\nError: {}",
-                    self.profile,
-                    stderr.trim()
-                );
-            } else if stderr.contains("ExpiredToken") {
-                anyhow::bail!(
+            match error.kind {
+                AwsErrorKind::ExpiredToken => anyhow::bail!(
                     "AWS credentials have expired for profile '{}'. Please refresh your credentials.\nError: {}",
                     self.profile,
-                    stderr.trim()
-                );
-            } else {
-                anyhow::bail!(
+                    error.message
+                ),
+                AwsErrorKind::InvalidCredentials => anyhow::bail!(
+                    "AWS credentials are missing or invalid for profile '{}'. Please configure or refresh your credentials.\nError: {}",
+                    self.profile,
+                    error.message
+                ),
+                _ => anyhow::bail!(
                     "Failed to validate AWS credentials for profile '{}'.\nError: {}",
                     self.profile,
-                    stderr.trim()
-                );
+                    error.message
+                ),
             }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::executor::FakeExecutor;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_execute_parses_json_from_executor() {
+        let fake = FakeExecutor::new().respond(
+            &["sts", "get-caller-identity"],
+            json!({ "Account": "123456789012" }),
+        );
+        let cli = AwsCli::with_executor("default".to_string(), Arc::new(fake));
+
+        let identity = cli.execute(&["sts", "get-caller-identity"]).await.unwrap();
+
+        assert_eq!(identity["Account"], "123456789012");
+    }
+
+    #[tokio::test]
+    async fn test_run_targets_configured_endpoint() {
+        let fake = Arc::new(FakeExecutor::with_default(json!({})));
+        let endpoints = Endpoints::parse(&["http://localhost:4566".to_string()]).unwrap();
+        let cli =
+            AwsCli::with_executor("default".to_string(), fake.clone()).with_endpoints(endpoints);
+
+        cli.validate_credentials().await.unwrap();
+        cli.execute(&["ec2", "describe-regions"]).await.unwrap();
+
+        assert_eq!(
+            fake.calls(),
+            vec![
+                vec![
+                    "sts",
+                    "get-caller-identity",
+                    "--endpoint-url",
+                    "http://localhost:4566"
+                ],
+                vec![
+                    "ec2",
+                    "describe-regions",
+                    "--endpoint-url",
+                    "http://localhost:4566"
+                ],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_times_out_hung_calls() {
+        struct HangingExecutor;
+
+        #[async_trait::async_trait]
+        impl CommandExecutor for HangingExecutor {
+            async fn run(&self, _request: &CliRequest) -> Result<CliOutput> {
+                std::future::pending().await
+            }
+        }
+
+        let cli = AwsCli::with_executor("default".to_string(), Arc::new(HangingExecutor))
+            .with_call_timeout(Duration::from_millis(10));
+
+        let error = cli
+            .execute(&["ec2", "describe-instances"])
+            .await
+            .unwrap_err();
+
+        assert_eq!(AwsErrorKind::of(&error), AwsErrorKind::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn test_throttling_halves_adaptive_limit() {
+        let args = ["ec2", "describe-instances"];
+        let fake = FakeExecutor::new()
+            .fail(
+                &args,
+                "An error occurred (RequestLimitExceeded) when calling the DescribeInstances operation",
+            )
+            .respond(&args, json!({ "Reservations": [] }));
+        let adaptive = Arc::new(AdaptiveConcurrency::default());
+        let cli = AwsCli::with_executor("default".to_string(), Arc::new(fake))
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 2,
+                base_delay: Duration::from_millis(1),
+                max_delay: Duration::from_millis(1),
+            })
+            .with_adaptive_concurrency(Arc::clone(&adaptive));
+        assert_eq!(cli.concurrency_for("ec2", 10), 4);
+
+        cli.execute(&args).await.unwrap();
+
+        let summary = adaptive.summary();
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].service, "ec2");
+        assert_eq!(summary[0].limit, 2);
+        assert_eq!(summary[0].decreases, 1);
+        // Fan-outs over the throttled service shrink with it
+        assert_eq!(cli.concurrency_for("ec2", 10), 2);
+        assert_eq!(cli.concurrency_for("ec2", 1), 1);
+    }
+
+    #[tokio::test]
+    async fn test_identical_calls_share_one_invocation() {
+        let fake = Arc::new(FakeExecutor::with_default(json!({ "clusterArns": [] })));
+        let cache = Arc::new(ResponseCache::default());
+        let cli = AwsCli::with_executor("default".to_string(), fake.clone())
+            .with_response_cache(Arc::clone(&cache));
+
+        let args = ["ecs", "list-clusters", "--region", "us-east-1"];
+        let (first, second) = tokio::join!(cli.execute(&args), cli.execute(&args));
+        cli.execute(&args).await.unwrap();
+        cli.execute(&["ecs", "list-clusters", "--region", "eu-west-1"])
+            .await
+            .unwrap();
+
+        assert_eq!(first.unwrap(), second.unwrap());
+        assert_eq!(fake.calls().len(), 2);
+        assert_eq!(cache.hits(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_execute_retries_throttled_calls() {
+        let args = ["ec2", "describe-instances"];
+        let throttled =
+            "An error occurred (RequestLimitExceeded) when calling the DescribeInstances operation";
+        let fake = FakeExecutor::new()
+            .fail(&args, throttled)
+            .fail(&args, throttled)
+            .respond(&args, json!({ "Reservations": [] }));
+        let cli = AwsCli::with_executor("default".to_string(), Arc::new(fake)).with_retry_policy(
+            RetryPolicy {
+                max_attempts: 3,
+                base_delay: std::time::Duration::from_millis(1),
+                max_delay: std::time::Duration::from_millis(1),
+            },
+        );
+
+        let response = cli.execute(&args).await.unwrap();
+
+        assert_eq!(response, json!({ "Reservations": [] }));
+        let summary = cli.retry_summary();
+        assert_eq!(summary.retries, 2);
+        assert_eq!(summary.throttled, 2);
+        assert_eq!(summary.exhausted, 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_gives_up_after_max_attempts() {
+        let args = ["ec2", "describe-instances"];
+        let fake = FakeExecutor::new().fail(&args, "An error occurred (Throttling) when calling");
+        let cli = AwsCli::with_executor("default".to_string(), Arc::new(fake)).with_retry_policy(
+            RetryPolicy {
+                max_attempts: 2,
+                base_delay: std::time::Duration::from_millis(1),
+                max_delay: std::time::Duration::from_millis(1),
+            },
+        );
+
+        assert!(cli.execute(&args).await.is_err());
+        assert_eq!(cli.retry_summary().retries, 1);
+        assert_eq!(cli.retry_summary().exhausted, 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_fails_on_non_zero_exit() {
+        let fake = FakeExecutor::new().fail(
+            &["iam", "list-users"],
+            "An error occurred (AccessDenied) when calling the ListUsers operation",
+        );
+        let cli = AwsCli::with_executor("default".to_string(), Arc::new(fake));
+
+        let err = cli.execute(&["iam", "list-users"]).await.unwrap_err();
+
+        assert!(err.to_string().contains("AccessDenied"));
+        assert_eq!(AwsErrorKind::of(&err), AwsErrorKind::AccessDenied);
+    }
+
+    #[tokio::test]
+    async fn test_validate_credentials_reports_expired_token() {
+        let fake = FakeExecutor::new().fail(
+            &["sts", "get-caller-identity"],
+            "An error occurred (ExpiredToken) when calling the GetCallerIdentity operation: The security token included in the request is expired",
+        );
+        let cli = AwsCli::with_executor("prod".to_string(), Arc::new(fake));
+
+        let err = cli.validate_credentials().await.unwrap_err();
+
+        assert!(err.to_string().contains("have expired for profile 'prod'"));
+    }
+
+    fn assume_role_response(expires_in: chrono::TimeDelta) -> serde_json::Value {
+        json!({
+            "Credentials": {
+                "AccessKeyId": "ASIAEXAMPLE",
+                "SecretAccessKey": "secret",
+                "SessionToken": "token",
+                "Expiration": (chrono::Utc::now() + expires_in).to_rfc3339(),
+            }
+        })
+    }
+
+    fn assume_role_calls(fake: &FakeExecutor) -> Vec<Vec<String>> {
+        fake.calls()
+            .into_iter()
+            .filter(|args| args[..2] == ["sts", "assume-role"])
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_assumed_role_renewed_before_expiry() {
+        let role_arn = "arn:aws:iam::222222222222:role/Audit";
+        let expiring = Arc::new(FakeExecutor::with_default(assume_role_response(
+            chrono::TimeDelta::minutes(1),
+        )));
+        let cli = AwsCli::with_executor("default".to_string(), expiring.clone())
+            .with_role_duration(3600)
+            .assume_role(role_arn, "us-east-1")
+            .await
+            .unwrap();
+
+        cli.execute_uncached(&["ec2", "describe-vpcs"])
+            .await
+            .unwrap();
+        cli.execute_uncached(&["ec2", "describe-vpcs"])
+            .await
+            .unwrap();
+
+        let calls = assume_role_calls(&expiring);
+        assert_eq!(calls.len(), 3);
+        assert!(calls[0].ends_with(&["--duration-seconds".to_string(), "3600".to_string()]));
+
+        let valid = Arc::new(FakeExecutor::with_default(assume_role_response(
+            chrono::TimeDelta::hours(1),
+        )));
+        let cli = AwsCli::with_executor("default".to_string(), valid.clone())
+            .assume_role(role_arn, "us-east-1")
+            .await
+            .unwrap();
+
+        cli.execute_uncached(&["ec2", "describe-vpcs"])
+            .await
+            .unwrap();
+
+        assert_eq!(assume_role_calls(&valid).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_audit_records_effective_region() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = Arc::new(AuditLog::create(&dir.path().join("audit.jsonl")).unwrap());
+        let role_arn = "arn:aws:iam::222222222222:role/Audit";
+        // `configure get region` fails: the profile has no region configured
+        let fake = FakeExecutor::new()
+            .respond(&["ec2", "describe-vpcs"], json!({ "Vpcs": [] }))
+            .respond(
+                &["ec2", "describe-vpcs", "--region", "eu-west-1"],
+                json!({ "Vpcs": [] }),
+            )
+            .respond(&["iam", "list-roles"], json!({ "Roles": [] }))
+            .respond(
+                &[
+                    "sts",
+                    "assume-role",
+                    "--role-arn",
+                    role_arn,
+                    "--role-session-name",
+                    "cloud-collector",
+                ],
+                assume_role_response(chrono::TimeDelta::hours(1)),
+            );
+        let cli = AwsCli::with_executor("default".to_string(), Arc::new(fake))
+            .with_audit_log(Arc::clone(&audit));
+
+        cli.execute(&["ec2", "describe-vpcs"]).await.unwrap();
+        cli.execute(&["ec2", "describe-vpcs", "--region", "eu-west-1"])
+            .await
+            .unwrap();
+        let role_cli = cli.assume_role(role_arn, "us-west-2").await.unwrap();
+        role_cli.execute(&["iam", "list-roles"]).await.unwrap();
+
+        let mut regions: Vec<(String, Option<String>)> = audit
+            .slowest(audit.len())
+            .into_iter()
+            .map(|record| (record.operation, record.region))
+            .collect();
+        regions.sort();
+        let region = |region: &str| Some(region.to_string());
+        assert_eq!(
+            regions,
+            vec![
+                ("assume-role".to_string(), region(DEFAULT_REGION)),
+                ("describe-vpcs".to_string(), region(DEFAULT_REGION)),
+                ("describe-vpcs".to_string(), region("eu-west-1")),
+                ("list-roles".to_string(), region("us-west-2")),
+            ]
+        );
+    }
+}
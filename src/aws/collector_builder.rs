@@ -4,6 +4,7 @@
 // with minimal boilerplate code.
 
 use crate::aws::cli::AwsCli;
+use crate::aws::permissions::CliOperation;
 use crate::models::ResourceCollection;
 use crate::parallel;
 use anyhow::Result;
@@ -71,6 +72,7 @@ struct DetailCollectionParams<'a> {
     service: &'a str,
     cli: &'a AwsCli,
     region: &'a str,
+    command_region: Option<&'a str>,
     resource_type: &'a str,
     timestamp: &'a str,
     list_command: Vec<String>,
@@ -163,6 +165,38 @@ impl CollectorBuilder {
         self
     }
 
+    /// AWS CLI operations the configured resources may call
+    pub fn operations(&self) -> Vec<CliOperation> {
+        let mut operations = Vec::new();
+        for resource in &self.resources {
+            match &resource.mode {
+                CollectionMode::SimpleList { command } => {
+                    operations.extend(CliOperation::from_args(command));
+                }
+                CollectionMode::BatchCommands { commands } => {
+                    operations.extend(
+                        commands
+                            .iter()
+                            .filter_map(|(_, command)| CliOperation::from_args(command)),
+                    );
+                }
+                CollectionMode::ListWithDetails {
+                    list_command,
+                    detail_templates,
+                    ..
+                } => {
+                    operations.extend(CliOperation::from_args(list_command));
+                    operations.extend(
+                        detail_templates
+                            .iter()
+                            .map(|t| CliOperation::new(&t.service, &t.operation)),
+                    );
+                }
+            }
+        }
+        operations
+    }
+
     /// Collect all resources
     pub async fn collect(self, cli: &AwsCli) -> Result<Vec<ResourceCollection>> {
         self.collect_with_region(cli, "").await
@@ -184,6 +218,14 @@ impl CollectorBuilder {
             RegionMode::Custom(r) => r.clone(),
         };
 
+        // Region every command is sent to; global services use the default endpoint
+        let command_region = match &self.region_mode {
+            RegionMode::Regional if !region.is_empty() => Some(region.to_string()),
+            RegionMode::Regional | RegionMode::Global => None,
+            RegionMode::Custom(r) => Some(r.clone()),
+        };
+        let command_region = command_region.as_deref();
+
         // Store service name to avoid borrow issues
         let service = self.service.clone();
 
@@ -196,7 +238,7 @@ impl CollectorBuilder {
                         &actual_region,
                         &resource_config.resource_type,
                         &timestamp,
-                        command,
+                        with_region(command, command_region),
                     )
                     .await;
                     all_collections.extend(collections);
@@ -208,7 +250,10 @@ impl CollectorBuilder {
                         cli,
                         &actual_region,
                         &timestamp,
-                        commands,
+                        commands
+                            .into_iter()
+                            .map(|(rt, cmd)| (rt, with_region(cmd, command_region)))
+                            .collect(),
                     )
                     .await;
                     all_collections.extend(collections);
@@ -225,9 +270,10 @@ impl CollectorBuilder {
                         service: &service,
                         cli,
                         region: &actual_region,
+                        command_region,
                         resource_type: &resource_config.resource_type,
                         timestamp: &timestamp,
-                        list_command,
+                        list_command: with_region(list_command, command_region),
                         array_key: &array_key,
                         identifier_key: &identifier_key,
                         detail_templates,
@@ -253,18 +299,16 @@ impl CollectorBuilder {
         command: Vec<String>,
     ) -> Vec<ResourceCollection> {
         let args: Vec<&str> = command.iter().map(|s| s.as_str()).collect();
-
-        if let Ok(resources) = cli.execute(&args).await {
-            vec![ResourceCollection {
-                service: service.to_string(),
-                region: region.to_string(),
-                resource_type: resource_type.to_string(),
-                resources,
-                collected_at: timestamp.to_string(),
-            }]
-        } else {
-            Vec::new()
-        }
+        let result = cli.execute(&args).await;
+
+        vec![ResourceCollection::from_result(
+            service,
+            region,
+            resource_type,
+            &args,
+            result,
+            timestamp,
+        )]
     }
 
     async fn collect_batch_commands_impl(
@@ -290,23 +334,32 @@ impl CollectorBuilder {
         let args: Vec<&str> = params.list_command.iter().map(|s| s.as_str()).collect();
         let list_response = match params.cli.execute(&args).await {
             Ok(resp) => resp,
-            Err(_) => return Vec::new(),
+            Err(e) => {
+                return vec![ResourceCollection::from_result(
+                    params.service,
+                    params.region,
+                    params.resource_type,
+                    &args,
+                    Err(e),
+                    params.timestamp,
+                )]
+            }
         };
 
         // Extract array of resources
-        let resources = match parallel::extract_array(&list_response, params.array_key) {
-            Some(arr) => arr,
-            None => return Vec::new(),
-        };
+        let resources =
+            parallel::extract_array(&list_response, params.array_key).unwrap_or_default();
 
-        // Fetch details for each resource in parallel
+        // Fetch details for each resource in parallel, no faster than the
+        // service's adaptive limit allows
+        let detail_concurrency = params.cli.concurrency_for(args[0], params.concurrency);
         let cli_clone = params.cli.clone();
-        let region_clone = params.region.to_string();
+        let region_clone = params.command_region.unwrap_or_default().to_string();
         let identifier_key_clone = params.identifier_key.to_string();
         let detail_templates = params.detail_templates;
 
-        let detailed_resources =
-            parallel::fetch_details_parallel(resources, params.concurrency, move |resource| {
+        let results =
+            parallel::fetch_details_parallel(resources, detail_concurrency, move |resource| {
                 let cli = cli_clone.clone();
                 let region = region_clone.clone();
                 let identifier_key = identifier_key_clone.clone();
@@ -316,7 +369,7 @@ impl CollectorBuilder {
                     // Extract identifier
                     let identifier = match parallel::extract_string(&resource, &identifier_key) {
                         Some(id) => id,
-                        None => return resource,
+                        None => return (resource, Vec::new()),
                     };
 
                     // Build detail configs from templates
@@ -341,17 +394,187 @@ impl CollectorBuilder {
             })
             .await;
 
+        let (detailed_resources, errors) = parallel::split_details(results);
+
         // Wrap in response object
         let response = json!({
             params.array_key: detailed_resources
         });
 
-        vec![ResourceCollection {
-            service: params.service.to_string(),
-            region: params.region.to_string(),
-            resource_type: params.resource_type.to_string(),
-            resources: response,
-            collected_at: params.timestamp.to_string(),
-        }]
+        vec![ResourceCollection::new(
+            params.service,
+            params.region,
+            params.resource_type,
+            response,
+            params.timestamp,
+        )
+        .with_errors(errors)]
+    }
+}
+
+/// Append `--region` to a command unless it already targets a region
+fn with_region(mut command: Vec<String>, region: Option<&str>) -> Vec<String> {
+    if let Some(region) = region {
+        if !command.iter().any(|arg| arg == "--region") {
+            command.push("--region".to_string());
+            command.push(region.to_string());
+        }
+    }
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::executor::FakeExecutor;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_simple_list_wraps_response() {
+        let fake = FakeExecutor::new()
+            .respond(
+                &["iam", "list-users"],
+                json!({ "Users": [{ "UserName": "alice" }] }),
+            )
+            .fail(
+                &["iam", "list-roles"],
+                "An error occurred (AccessDenied) when calling the ListRoles operation",
+            );
+        let cli = AwsCli::with_executor("default".to_string(), Arc::new(fake));
+
+        let collections = CollectorBuilder::new("iam", RegionMode::Global)
+            .add_simple_list("users", vec!["iam", "list-users"])
+            .add_simple_list("roles", vec!["iam", "list-roles"])
+            .collect(&cli)
+            .await
+            .unwrap();
+
+        assert_eq!(collections.len(), 2);
+        assert_eq!(collections[0].region, "global");
+        assert_eq!(collections[0].resource_type, "users");
+        assert_eq!(collections[0].resources["Users"][0]["UserName"], "alice");
+        assert_eq!(collections[1].resource_type, "roles");
+        assert!(collections[1].is_failed());
+    }
+
+    #[tokio::test]
+    async fn test_detailed_resource_fetches_details() {
+        let fake = FakeExecutor::new()
+            .respond(
+                &["sns", "list-topics", "--region", "us-east-1"],
+                json!({ "Topics": [{ "TopicArn": "arn:aws:sns:us-east-1:123456789012:alerts" }] }),
+            )
+            .respond(
+                &[
+                    "sns",
+                    "get-topic-attributes",
+                    "--topic-arn",
+                    "arn:aws:sns:us-east-1:123456789012:alerts",
+                    "--region",
+                    "us-east-1",
+                ],
+                json!({ "Attributes": { "DisplayName": "Alerts" } }),
+            );
+        let cli = AwsCli::with_executor("default".to_string(), Arc::new(fake));
+
+        let collections = CollectorBuilder::new("sns", RegionMode::Regional)
+            .add_detailed_resource(
+                "topics",
+                vec!["sns", "list-topics"],
+                "Topics",
+                "TopicArn",
+                2,
+                vec![
+                    DetailTemplate::new("Attributes", "sns", "get-topic-attributes", "--topic-arn"),
+                    DetailTemplate::new("Tags", "sns", "list-tags-for-resource", "--resource-arn"),
+                ],
+            )
+            .collect_with_region(&cli, "us-east-1")
+            .await
+            .unwrap();
+
+        let topic = &collections[0].resources["Topics"][0];
+        assert_eq!(topic["Attributes"]["Attributes"]["DisplayName"], "Alerts");
+        assert!(topic.get("Tags").is_none());
+        assert_eq!(collections[0].errors.len(), 1);
+        assert!(collections[0].errors[0]
+            .command
+            .starts_with("sns list-tags-for-resource"));
+    }
+
+    #[tokio::test]
+    async fn test_every_command_targets_the_task_region() {
+        let item = json!({ "FunctionName": "f" });
+        let fake = Arc::new(FakeExecutor::with_default(json!({ "Functions": [item] })));
+        let cli = AwsCli::with_executor("default".to_string(), fake.clone());
+
+        CollectorBuilder::new("lambda", RegionMode::Regional)
+            .add_simple_list("layers", vec!["lambda", "list-layers"])
+            .add_batch_commands(vec![
+                (
+                    "code-signing-configs",
+                    vec!["lambda", "list-code-signing-configs"],
+                ),
+                (
+                    "pinned",
+                    vec!["lambda", "list-layers", "--region", "eu-west-1"],
+                ),
+            ])
+            .add_detailed_resource(
+                "functions",
+                vec!["lambda", "list-functions"],
+                "Functions",
+                "FunctionName",
+                2,
+                vec![DetailTemplate::new(
+                    "Aliases",
+                    "lambda",
+                    "list-aliases",
+                    "--function-name",
+                )],
+            )
+            .collect_with_region(&cli, "us-west-2")
+            .await
+            .unwrap();
+
+        let calls = fake.calls();
+        assert_eq!(calls.len(), 5);
+        for call in calls {
+            let regions: Vec<_> = call.windows(2).filter(|w| w[0] == "--region").collect();
+            assert_eq!(regions.len(), 1, "{:?}", call);
+            let expected = if call.contains(&"eu-west-1".to_string()) {
+                "eu-west-1"
+            } else {
+                "us-west-2"
+            };
+            assert_eq!(regions[0][1], expected, "{:?}", call);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_region_mode_controls_command_region() {
+        for (mode, expected) in [
+            (RegionMode::Global, None),
+            (
+                RegionMode::Custom("us-east-1".to_string()),
+                Some("us-east-1"),
+            ),
+        ] {
+            let fake = Arc::new(FakeExecutor::with_default(json!({})));
+            let cli = AwsCli::with_executor("default".to_string(), fake.clone());
+
+            CollectorBuilder::new("iam", mode)
+                .add_simple_list("users", vec!["iam", "list-users"])
+                .collect_with_region(&cli, "us-west-2")
+                .await
+                .unwrap();
+
+            let call = &fake.calls()[0];
+            let region = call
+                .iter()
+                .position(|arg| arg == "--region")
+                .map(|i| call[i + 1].as_str());
+            assert_eq!(region, expected);
+        }
     }
 }
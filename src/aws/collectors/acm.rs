@@ -1,7 +1,8 @@
 // ACM (AWS Certificate Manager) resource collector
 use super::ResourceCollector;
 use crate::aws::cli::AwsCli;
-use crate::models::ResourceCollection;
+use crate::aws::permissions::CliOperation;
+use crate::models::{CollectionError, ResourceCollection};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::json;
@@ -15,37 +16,46 @@ impl ResourceCollector for AcmCollector {
         let timestamp = chrono::Utc::now().to_rfc3339();
 
         // Collect certificates with tags
-        if let Ok(certs_response) = cli
-            .execute(&["acm", "list-certificates", "--region", region])
-            .await
-        {
-            let certs = crate::parallel::extract_array(&certs_response, "CertificateSummaryList")
-                .unwrap_or_default();
+        let args = ["acm", "list-certificates", "--region", region];
+        match cli.execute(&args).await {
+            Ok(certs_response) => {
+                let certs =
+                    crate::parallel::extract_array(&certs_response, "CertificateSummaryList")
+                        .unwrap_or_default();
 
-            // Process certificates in parallel with concurrency limit of 10
-            let detailed_certs_results =
-                crate::parallel::fetch_details_parallel(certs, 10, |cert| {
-                    let cli = cli.clone();
-                    let region = region.to_string();
-                    async move {
-                        let cert_arn =
-                            match crate::parallel::extract_string(&cert, "CertificateArn") {
-                                Some(arn) => arn,
-                                None => return serde_json::Value::Null,
-                            };
+                // Process certificates in parallel with concurrency limit of 10, or the
+                // service's adaptive limit when lower
+                let concurrency = cli.concurrency_for(args[0], 10);
+                let detailed_certs_results =
+                    crate::parallel::fetch_details_parallel(certs, concurrency, |cert| {
+                        let cli = cli.clone();
+                        let region = region.to_string();
+                        async move {
+                            let cert_arn =
+                                match crate::parallel::extract_string(&cert, "CertificateArn") {
+                                    Some(arn) => arn,
+                                    None => return (serde_json::Value::Null, Vec::new()),
+                                };
 
-                        // Get certificate details first
-                        if let Ok(cert_details) = cli
-                            .execute(&[
+                            // Get certificate details first
+                            let describe_args = [
                                 "acm",
                                 "describe-certificate",
                                 "--certificate-arn",
                                 &cert_arn,
                                 "--region",
                                 &region,
-                            ])
-                            .await
-                        {
+                            ];
+                            let cert_details = match cli.execute(&describe_args).await {
+                                Ok(details) => details,
+                                Err(e) => {
+                                    let errors = CollectionError::from_failure(&describe_args, &e)
+                                        .into_iter()
+                                        .collect();
+                                    return (serde_json::Value::Null, errors);
+                                }
+                            };
+
                             if let Some(certificate) = cert_details.get("Certificate").cloned() {
                                 // Get tags for the certificate
                                 let detail_configs = vec![crate::parallel::DetailConfig::new(
@@ -54,7 +64,7 @@ impl ResourceCollector for AcmCollector {
                                         "acm".to_string(),
                                         "list-tags-for-certificate".to_string(),
                                         "--certificate-arn".to_string(),
-                                        cert_arn,
+                                        cert_arn.clone(),
                                     ],
                                 )];
 
@@ -66,32 +76,56 @@ impl ResourceCollector for AcmCollector {
                                 )
                                 .await;
                             }
-                        }
 
-                        serde_json::Value::Null
-                    }
-                })
-                .await;
+                            (serde_json::Value::Null, Vec::new())
+                        }
+                    })
+                    .await;
+                let (detailed_certs, errors) =
+                    crate::parallel::split_details(detailed_certs_results);
 
-            // Filter out null values
-            let detailed_certs: Vec<_> = detailed_certs_results
-                .into_iter()
-                .filter(|v| !v.is_null())
-                .collect();
+                // Filter out null values
+                let detailed_certs: Vec<_> = detailed_certs
+                    .into_iter()
+                    .filter(|v| !v.is_null())
+                    .collect();
 
-            let detailed_response = json!({
-                "Certificates": detailed_certs
-            });
+                let detailed_response = json!({
+                    "Certificates": detailed_certs
+                });
 
-            collections.push(ResourceCollection {
-                service: "acm".to_string(),
-                region: region.to_string(),
-                resource_type: "certificates".to_string(),
-                resources: detailed_response,
-                collected_at: timestamp,
-            });
+                collections.push(
+                    ResourceCollection::new(
+                        "acm",
+                        region,
+                        "certificates",
+                        detailed_response,
+                        &timestamp,
+                    )
+                    .with_errors(errors),
+                );
+            }
+            Err(e) => collections.push(ResourceCollection::from_result(
+                "acm",
+                region,
+                "certificates",
+                &args,
+                Err(e),
+                &timestamp,
+            )),
         }
 
         Ok(collections)
     }
+
+    fn operations(&self) -> Vec<CliOperation> {
+        [
+            ("acm", "list-certificates"),
+            ("acm", "describe-certificate"),
+            ("acm", "list-tags-for-certificate"),
+        ]
+        .into_iter()
+        .map(|(service, operation)| CliOperation::new(service, operation))
+        .collect()
+    }
 }
@@ -1,7 +1,8 @@
 // CloudFormation resource collector
 use super::ResourceCollector;
 use crate::aws::cli::AwsCli;
-use crate::models::ResourceCollection;
+use crate::aws::permissions::CliOperation;
+use crate::models::{CollectionError, ResourceCollection};
 use anyhow::Result;
 use async_trait::async_trait;
 
@@ -38,47 +39,76 @@ impl ResourceCollector for CloudFormationCollector {
         .await;
 
         // Collect change sets (depends on stacks, so run after)
-        if let Ok(stacks_response) = cli
-            .execute(&["cloudformation", "describe-stacks", "--region", region])
-            .await
-        {
-            if let Some(stacks) = stacks_response.get("Stacks").and_then(|s| s.as_array()) {
+        let args = ["cloudformation", "describe-stacks", "--region", region];
+        match cli.execute(&args).await {
+            Ok(stacks_response) => {
                 let mut all_change_sets = Vec::new();
+                let mut errors = Vec::new();
 
-                for stack in stacks {
+                let stacks = stacks_response
+                    .get("Stacks")
+                    .and_then(|s| s.as_array())
+                    .cloned()
+                    .unwrap_or_default();
+
+                for stack in &stacks {
                     if let Some(stack_name) = stack.get("StackName").and_then(|n| n.as_str()) {
-                        if let Ok(change_sets) = cli
-                            .execute(&[
-                                "cloudformation",
-                                "list-change-sets",
-                                "--stack-name",
-                                stack_name,
-                                "--region",
-                                region,
-                            ])
-                            .await
-                        {
-                            if let Some(cs_list) =
-                                change_sets.get("Summaries").and_then(|cs| cs.as_array())
-                            {
-                                all_change_sets.extend(cs_list.clone());
+                        let change_sets_args = [
+                            "cloudformation",
+                            "list-change-sets",
+                            "--stack-name",
+                            stack_name,
+                            "--region",
+                            region,
+                        ];
+                        match cli.execute(&change_sets_args).await {
+                            Ok(change_sets) => {
+                                if let Some(cs_list) =
+                                    change_sets.get("Summaries").and_then(|cs| cs.as_array())
+                                {
+                                    all_change_sets.extend(cs_list.clone());
+                                }
+                            }
+                            Err(e) => {
+                                errors.extend(CollectionError::from_failure(&change_sets_args, &e))
                             }
                         }
                     }
                 }
 
-                if !all_change_sets.is_empty() {
-                    collections.push(ResourceCollection {
-                        service: "cloudformation".to_string(),
-                        region: region.to_string(),
-                        resource_type: "change-sets".to_string(),
-                        resources: serde_json::json!({ "ChangeSets": all_change_sets }),
-                        collected_at: timestamp,
-                    });
-                }
+                collections.push(
+                    ResourceCollection::new(
+                        "cloudformation",
+                        region,
+                        "change-sets",
+                        serde_json::json!({ "ChangeSets": all_change_sets }),
+                        &timestamp,
+                    )
+                    .with_errors(errors),
+                );
             }
+            Err(e) => collections.push(ResourceCollection::from_result(
+                "cloudformation",
+                region,
+                "change-sets",
+                &args,
+                Err(e),
+                &timestamp,
+            )),
         }
 
         Ok(collections)
     }
+
+    fn operations(&self) -> Vec<CliOperation> {
+        [
+            ("cloudformation", "describe-stacks"),
+            ("cloudformation", "list-stack-sets"),
+            ("cloudformation", "list-exports"),
+            ("cloudformation", "list-change-sets"),
+        ]
+        .into_iter()
+        .map(|(service, operation)| CliOperation::new(service, operation))
+        .collect()
+    }
 }
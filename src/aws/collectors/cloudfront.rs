@@ -1,7 +1,9 @@
 // CloudFront resource collector
 use super::ResourceCollector;
+use crate::aws::arn::Arn;
 use crate::aws::cli::AwsCli;
-use crate::models::ResourceCollection;
+use crate::aws::permissions::CliOperation;
+use crate::models::{CollectionError, ResourceCollection};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::json;
@@ -17,131 +19,170 @@ impl ResourceCollector for CloudFrontCollector {
         // CloudFront is a global service
 
         // Collect distributions
-        if let Ok(distributions_response) = cli.execute(&["cloudfront", "list-distributions"]).await
-        {
-            let mut detailed_distributions = Vec::new();
-
-            if let Some(dist_list) = distributions_response.get("DistributionList") {
-                if let Some(distributions) = dist_list.get("Items").and_then(|d| d.as_array()) {
+        let args = ["cloudfront", "list-distributions"];
+        match cli.execute(&args).await {
+            Ok(distributions_response) => {
+                let mut detailed_distributions = Vec::new();
+                let mut errors = Vec::new();
+
+                if let Some(distributions) = distributions_response
+                    .get("DistributionList")
+                    .and_then(|l| l.get("Items"))
+                    .and_then(|d| d.as_array())
+                {
                     for distribution in distributions {
                         if let Some(dist_id) = distribution.get("Id").and_then(|id| id.as_str()) {
                             let mut dist_details = distribution.clone();
 
                             // Get distribution configuration
-                            if let Ok(config) = cli
-                                .execute(&[
-                                    "cloudfront",
-                                    "get-distribution-config",
-                                    "--id",
-                                    dist_id,
-                                ])
-                                .await
-                            {
-                                dist_details
-                                    .as_object_mut()
-                                    .unwrap()
-                                    .insert("Config".to_string(), config);
+                            let config_args =
+                                ["cloudfront", "get-distribution-config", "--id", dist_id];
+                            match cli.execute(&config_args).await {
+                                Ok(config) => {
+                                    dist_details
+                                        .as_object_mut()
+                                        .unwrap()
+                                        .insert("Config".to_string(), config);
+                                }
+                                Err(e) => {
+                                    errors.extend(CollectionError::from_failure(&config_args, &e))
+                                }
                             }
 
-                            // Get distribution tags
-                            if let Ok(tags) = cli
-                                .execute(&[
-                                    "cloudfront",
-                                    "list-tags-for-resource",
-                                    "--resource",
-                                    &format!("arn:aws:cloudfront::*:distribution/{}", dist_id),
-                                ])
-                                .await
-                            {
-                                dist_details
-                                    .as_object_mut()
-                                    .unwrap()
-                                    .insert("Tags".to_string(), tags);
+                            // Get distribution tags, which are looked up by the
+                            // distribution's full ARN
+                            let arn = match distribution.get("ARN").and_then(|a| a.as_str()) {
+                                Some(arn) => Some(arn.to_string()),
+                                None => cli.caller_arn().await.ok().and_then(|caller| {
+                                    Arn::for_resource(
+                                        &caller.partition,
+                                        "cloudfront",
+                                        "distributions",
+                                        "global",
+                                        &caller.account,
+                                        dist_id,
+                                    )
+                                    .map(|arn| arn.to_string())
+                                }),
+                            };
+                            if let Some(arn) = arn {
+                                let tags_args =
+                                    ["cloudfront", "list-tags-for-resource", "--resource", &arn];
+                                match cli.execute(&tags_args).await {
+                                    Ok(tags) => {
+                                        dist_details
+                                            .as_object_mut()
+                                            .unwrap()
+                                            .insert("Tags".to_string(), tags);
+                                    }
+                                    Err(e) => {
+                                        errors.extend(CollectionError::from_failure(&tags_args, &e))
+                                    }
+                                }
                             }
 
                             detailed_distributions.push(dist_details);
                         }
                     }
                 }
-            }
 
-            let detailed_response = json!({
-                "Distributions": detailed_distributions
-            });
-
-            collections.push(ResourceCollection {
-                service: "cloudfront".to_string(),
-                region: "global".to_string(),
-                resource_type: "distributions".to_string(),
-                resources: detailed_response,
-                collected_at: timestamp.clone(),
-            });
+                let detailed_response = json!({
+                    "Distributions": detailed_distributions
+                });
+
+                collections.push(
+                    ResourceCollection::new(
+                        "cloudfront",
+                        "global",
+                        "distributions",
+                        detailed_response,
+                        &timestamp,
+                    )
+                    .with_errors(errors),
+                );
+            }
+            Err(e) => collections.push(ResourceCollection::from_result(
+                "cloudfront",
+                "global",
+                "distributions",
+                &args,
+                Err(e),
+                &timestamp,
+            )),
         }
 
         // Collect origin access identities
-        if let Ok(oai_response) = cli
-            .execute(&["cloudfront", "list-cloud-front-origin-access-identities"])
-            .await
-        {
-            collections.push(ResourceCollection {
-                service: "cloudfront".to_string(),
-                region: "global".to_string(),
-                resource_type: "origin-access-identities".to_string(),
-                resources: oai_response,
-                collected_at: timestamp.clone(),
-            });
-        }
+        let args = ["cloudfront", "list-cloud-front-origin-access-identities"];
+        collections.push(ResourceCollection::from_result(
+            "cloudfront",
+            "global",
+            "origin-access-identities",
+            &args,
+            cli.execute(&args).await,
+            &timestamp,
+        ));
 
         // Collect cache policies
-        if let Ok(cache_policies) = cli.execute(&["cloudfront", "list-cache-policies"]).await {
-            collections.push(ResourceCollection {
-                service: "cloudfront".to_string(),
-                region: "global".to_string(),
-                resource_type: "cache-policies".to_string(),
-                resources: cache_policies,
-                collected_at: timestamp.clone(),
-            });
-        }
+        let args = ["cloudfront", "list-cache-policies"];
+        collections.push(ResourceCollection::from_result(
+            "cloudfront",
+            "global",
+            "cache-policies",
+            &args,
+            cli.execute(&args).await,
+            &timestamp,
+        ));
 
         // Collect origin request policies
-        if let Ok(origin_request_policies) = cli
-            .execute(&["cloudfront", "list-origin-request-policies"])
-            .await
-        {
-            collections.push(ResourceCollection {
-                service: "cloudfront".to_string(),
-                region: "global".to_string(),
-                resource_type: "origin-request-policies".to_string(),
-                resources: origin_request_policies,
-                collected_at: timestamp.clone(),
-            });
-        }
+        let args = ["cloudfront", "list-origin-request-policies"];
+        collections.push(ResourceCollection::from_result(
+            "cloudfront",
+            "global",
+            "origin-request-policies",
+            &args,
+            cli.execute(&args).await,
+            &timestamp,
+        ));
 
         // Collect response headers policies
-        if let Ok(response_headers_policies) = cli
-            .execute(&["cloudfront", "list-response-headers-policies"])
-            .await
-        {
-            collections.push(ResourceCollection {
-                service: "cloudfront".to_string(),
-                region: "global".to_string(),
-                resource_type: "response-headers-policies".to_string(),
-                resources: response_headers_policies,
-                collected_at: timestamp.clone(),
-            });
-        }
+        let args = ["cloudfront", "list-response-headers-policies"];
+        collections.push(ResourceCollection::from_result(
+            "cloudfront",
+            "global",
+            "response-headers-policies",
+            &args,
+            cli.execute(&args).await,
+            &timestamp,
+        ));
 
         // Collect CloudFront Functions
-        if let Ok(functions) = cli.execute(&["cloudfront", "list-functions"]).await {
-            collections.push(ResourceCollection {
-                service: "cloudfront".to_string(),
-                region: "global".to_string(),
-                resource_type: "functions".to_string(),
-                resources: functions,
-                collected_at: timestamp,
-            });
-        }
+        let args = ["cloudfront", "list-functions"];
+        collections.push(ResourceCollection::from_result(
+            "cloudfront",
+            "global",
+            "functions",
+            &args,
+            cli.execute(&args).await,
+            &timestamp,
+        ));
 
         Ok(collections)
     }
+
+    fn operations(&self) -> Vec<CliOperation> {
+        [
+            ("cloudfront", "list-distributions"),
+            ("cloudfront", "get-distribution-config"),
+            ("cloudfront", "list-tags-for-resource"),
+            ("sts", "get-caller-identity"),
+            ("cloudfront", "list-cloud-front-origin-access-identities"),
+            ("cloudfront", "list-cache-policies"),
+            ("cloudfront", "list-origin-request-policies"),
+            ("cloudfront", "list-response-headers-policies"),
+            ("cloudfront", "list-functions"),
+        ]
+        .into_iter()
+        .map(|(service, operation)| CliOperation::new(service, operation))
+        .collect()
+    }
 }
@@ -2,15 +2,15 @@
 use super::ResourceCollector;
 use crate::aws::cli::AwsCli;
 use crate::aws::collector_builder::{CollectorBuilder, DetailTemplate, RegionMode};
+use crate::aws::permissions::CliOperation;
 use crate::models::ResourceCollection;
 use anyhow::Result;
 use async_trait::async_trait;
 
 pub struct CloudWatchCollector;
 
-#[async_trait]
-impl ResourceCollector for CloudWatchCollector {
-    async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>> {
+impl CloudWatchCollector {
+    fn builder() -> CollectorBuilder {
         CollectorBuilder::new("cloudwatch", RegionMode::Regional)
             // Simple lists
             .add_simple_list("alarms", vec!["cloudwatch", "describe-alarms"])
@@ -42,7 +42,16 @@ impl ResourceCollector for CloudWatchCollector {
                     ),
                 ],
             )
-            .collect_with_region(cli, region)
-            .await
+    }
+}
+
+#[async_trait]
+impl ResourceCollector for CloudWatchCollector {
+    async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>> {
+        Self::builder().collect_with_region(cli, region).await
+    }
+
+    fn operations(&self) -> Vec<CliOperation> {
+        Self::builder().operations()
     }
 }
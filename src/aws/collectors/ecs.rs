@@ -1,296 +1,346 @@
 // ECS resource collector
 use super::ResourceCollector;
 use crate::aws::cli::AwsCli;
-use crate::models::ResourceCollection;
+use crate::aws::permissions::CliOperation;
+use crate::models::{CollectionError, ResourceCollection};
 use anyhow::Result;
 use async_trait::async_trait;
+use serde_json::Value;
 
 pub struct EcsCollector;
 
-#[async_trait]
-impl ResourceCollector for EcsCollector {
-    async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>> {
-        let mut collections = Vec::new();
-        let timestamp = chrono::Utc::now().to_rfc3339();
+/// Most clusters a single `describe-clusters` call accepts
+const DESCRIBE_CLUSTERS_BATCH: usize = 100;
 
-        // Collect ECS Clusters with capacity providers
-        if let Ok(cluster_arns) = cli
-            .execute(&["ecs", "list-clusters", "--region", region])
-            .await
-        {
-            if let Some(arns) = cluster_arns.get("clusterArns").and_then(|a| a.as_array()) {
-                if !arns.is_empty() {
-                    let arn_strings: Vec<String> = arns
-                        .iter()
-                        .filter_map(|a| a.as_str().map(String::from))
-                        .collect();
+/// Commands used to list and describe a per-cluster resource type
+struct ClusterResource<'a> {
+    list_operation: &'a str,
+    list_key: &'a str,
+    describe_operation: &'a str,
+    describe_flag: &'a str,
+    result_key: &'a str,
+    /// Most ARNs a single describe call accepts
+    batch_size: usize,
+}
+
+/// ARNs of every cluster in the region, or the errors of listing them
+async fn list_clusters(
+    cli: &AwsCli,
+    region: &str,
+) -> std::result::Result<Vec<String>, Vec<CollectionError>> {
+    let args = ["ecs", "list-clusters", "--region", region];
+    match cli.execute(&args).await {
+        Ok(response) => Ok(response
+            .get("clusterArns")
+            .and_then(|a| a.as_array())
+            .map(|arns| {
+                arns.iter()
+                    .filter_map(|a| a.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()),
+        Err(e) => Err(CollectionError::from_failure(&args, &e)
+            .into_iter()
+            .collect()),
+    }
+}
+
+/// Describe `arns` in batches of at most `batch_size` ARNs
+///
+/// Each call is `command`, a batch of ARNs, then `trailing`. Failed batches
+/// are recorded in `errors`; returns `None` if every batch failed.
+async fn describe_in_batches(
+    cli: &AwsCli,
+    command: &[&str],
+    arns: &[&str],
+    trailing: &[&str],
+    batch_size: usize,
+    result_key: &str,
+    errors: &mut Vec<CollectionError>,
+) -> Option<Vec<Value>> {
+    let mut items = Vec::new();
+    let mut described = false;
 
-                    if !arn_strings.is_empty() {
-                        let arn_args: Vec<&str> = arn_strings.iter().map(|s| s.as_str()).collect();
-                        let mut args = vec!["ecs", "describe-clusters", "--clusters"];
-                        args.extend(arn_args);
-                        args.push("--include");
-                        args.push("ATTACHMENTS");
-                        args.push("SETTINGS");
-                        args.push("STATISTICS");
-                        args.push("TAGS");
-                        args.push("--region");
-                        args.push(region);
+    for batch in arns.chunks(batch_size) {
+        let mut args = command.to_vec();
+        args.extend(batch);
+        args.extend(trailing);
 
-                        if let Ok(clusters) = cli.execute(&args).await {
-                            collections.push(ResourceCollection {
-                                service: "ecs".to_string(),
-                                region: region.to_string(),
-                                resource_type: "clusters".to_string(),
-                                resources: clusters,
-                                collected_at: timestamp.clone(),
-                            });
-                        }
-                    }
+        match cli.execute(&args).await {
+            Ok(response) => {
+                described = true;
+                if let Some(batch_items) = response.get(result_key).and_then(|i| i.as_array()) {
+                    items.extend(batch_items.iter().cloned());
                 }
             }
+            Err(e) => errors.extend(CollectionError::from_failure(&args, &e)),
         }
+    }
 
-        // Collect Capacity Providers
-        if let Ok(capacity_providers) = cli
-            .execute(&["ecs", "describe-capacity-providers", "--region", region])
-            .await
-        {
-            collections.push(ResourceCollection {
-                service: "ecs".to_string(),
-                region: region.to_string(),
-                resource_type: "capacity-providers".to_string(),
-                resources: capacity_providers,
-                collected_at: timestamp.clone(),
-            });
-        }
+    described.then_some(items)
+}
 
-        // Collect ECS Services
-        if let Ok(cluster_arns) = cli
-            .execute(&["ecs", "list-clusters", "--region", region])
-            .await
-        {
-            if let Some(arns) = cluster_arns.get("clusterArns").and_then(|a| a.as_array()) {
-                let mut all_services = Vec::new();
+/// List and describe a per-cluster resource type for every cluster
+///
+/// Failed commands are recorded in `errors`; the remaining clusters are still
+/// processed.
+async fn collect_per_cluster(
+    cli: &AwsCli,
+    region: &str,
+    cluster_arns: &[String],
+    resource: &ClusterResource<'_>,
+    errors: &mut Vec<CollectionError>,
+) -> Vec<Value> {
+    let mut all_items = Vec::new();
 
-                for arn in arns {
-                    if let Some(cluster_arn) = arn.as_str() {
-                        if let Ok(service_arns) = cli
-                            .execute(&[
-                                "ecs",
-                                "list-services",
-                                "--cluster",
-                                cluster_arn,
-                                "--region",
-                                region,
-                            ])
-                            .await
-                        {
-                            if let Some(svc_arns) =
-                                service_arns.get("serviceArns").and_then(|a| a.as_array())
-                            {
-                                if !svc_arns.is_empty() {
-                                    let svc_arn_strings: Vec<String> = svc_arns
-                                        .iter()
-                                        .filter_map(|a| a.as_str().map(String::from))
-                                        .collect();
+    for cluster_arn in cluster_arns {
+        let list_args = [
+            "ecs",
+            resource.list_operation,
+            "--cluster",
+            cluster_arn,
+            "--region",
+            region,
+        ];
+        let list_response = match cli.execute(&list_args).await {
+            Ok(response) => response,
+            Err(e) => {
+                errors.extend(CollectionError::from_failure(&list_args, &e));
+                continue;
+            }
+        };
 
-                                    if !svc_arn_strings.is_empty() {
-                                        let svc_arn_args: Vec<&str> =
-                                            svc_arn_strings.iter().map(|s| s.as_str()).collect();
-                                        let mut args = vec![
-                                            "ecs",
-                                            "describe-services",
-                                            "--cluster",
-                                            cluster_arn,
-                                            "--services",
-                                        ];
-                                        args.extend(svc_arn_args);
-                                        args.push("--region");
-                                        args.push(region);
+        let item_arns: Vec<&str> = list_response
+            .get(resource.list_key)
+            .and_then(|a| a.as_array())
+            .map(|arns| arns.iter().filter_map(|a| a.as_str()).collect())
+            .unwrap_or_default();
 
-                                        if let Ok(services) = cli.execute(&args).await {
-                                            if let Some(svc_list) =
-                                                services.get("services").and_then(|s| s.as_array())
-                                            {
-                                                all_services.extend(svc_list.clone());
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+        let items = describe_in_batches(
+            cli,
+            &[
+                "ecs",
+                resource.describe_operation,
+                "--cluster",
+                cluster_arn,
+                resource.describe_flag,
+            ],
+            &item_arns,
+            &["--region", region],
+            resource.batch_size,
+            resource.result_key,
+            errors,
+        )
+        .await;
+        all_items.extend(items.unwrap_or_default());
+    }
 
-                if !all_services.is_empty() {
-                    collections.push(ResourceCollection {
-                        service: "ecs".to_string(),
-                        region: region.to_string(),
-                        resource_type: "services".to_string(),
-                        resources: serde_json::json!({ "services": all_services }),
-                        collected_at: timestamp.clone(),
-                    });
-                }
-            }
-        }
+    all_items
+}
+
+#[async_trait]
+impl ResourceCollector for EcsCollector {
+    async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>> {
+        let mut collections = Vec::new();
+        let timestamp = chrono::Utc::now().to_rfc3339();
+
+        // Collect Capacity Providers
+        let args = ["ecs", "describe-capacity-providers", "--region", region];
+        collections.push(ResourceCollection::from_result(
+            "ecs",
+            region,
+            "capacity-providers",
+            &args,
+            cli.execute(&args).await,
+            &timestamp,
+        ));
 
         // Collect ECS Task Definitions
-        if let Ok(task_defs) = cli
-            .execute(&["ecs", "list-task-definitions", "--region", region])
-            .await
-        {
-            collections.push(ResourceCollection {
-                service: "ecs".to_string(),
-                region: region.to_string(),
-                resource_type: "task-definitions".to_string(),
-                resources: task_defs,
-                collected_at: timestamp.clone(),
-            });
-        }
+        let args = ["ecs", "list-task-definitions", "--region", region];
+        collections.push(ResourceCollection::from_result(
+            "ecs",
+            region,
+            "task-definitions",
+            &args,
+            cli.execute(&args).await,
+            &timestamp,
+        ));
 
-        // Collect ECS Tasks (including Fargate)
-        if let Ok(cluster_arns) = cli
-            .execute(&["ecs", "list-clusters", "--region", region])
-            .await
-        {
-            if let Some(arns) = cluster_arns.get("clusterArns").and_then(|a| a.as_array()) {
-                let mut all_tasks = Vec::new();
+        // Every cluster-scoped resource type starts from the cluster list, listed
+        // once; if it cannot be listed, none of them can be collected
+        let cluster_list = list_clusters(cli, region).await;
 
-                for arn in arns {
-                    if let Some(cluster_arn) = arn.as_str() {
-                        if let Ok(task_arns) = cli
-                            .execute(&[
-                                "ecs",
-                                "list-tasks",
-                                "--cluster",
-                                cluster_arn,
-                                "--region",
-                                region,
-                            ])
-                            .await
-                        {
-                            if let Some(t_arns) =
-                                task_arns.get("taskArns").and_then(|a| a.as_array())
-                            {
-                                if !t_arns.is_empty() {
-                                    let task_arn_strings: Vec<String> = t_arns
-                                        .iter()
-                                        .filter_map(|a| a.as_str().map(String::from))
-                                        .collect();
+        // Collect ECS Clusters with capacity providers
+        let clusters = match &cluster_list {
+            Ok(cluster_arns) if cluster_arns.is_empty() => ResourceCollection::new(
+                "ecs",
+                region,
+                "clusters",
+                serde_json::json!({ "clusters": [] }),
+                &timestamp,
+            ),
+            Ok(cluster_arns) => {
+                let arns: Vec<&str> = cluster_arns.iter().map(|s| s.as_str()).collect();
+                let mut errors = Vec::new();
+                let clusters = describe_in_batches(
+                    cli,
+                    &["ecs", "describe-clusters", "--clusters"],
+                    &arns,
+                    &[
+                        "--include",
+                        "ATTACHMENTS",
+                        "SETTINGS",
+                        "STATISTICS",
+                        "TAGS",
+                        "--region",
+                        region,
+                    ],
+                    DESCRIBE_CLUSTERS_BATCH,
+                    "clusters",
+                    &mut errors,
+                )
+                .await;
+                let resources = match clusters {
+                    Some(clusters) => serde_json::json!({ "clusters": clusters }),
+                    None => Value::Null,
+                };
+                ResourceCollection::new("ecs", region, "clusters", resources, &timestamp)
+                    .with_errors(errors)
+            }
+            Err(errors) => {
+                ResourceCollection::new("ecs", region, "clusters", Value::Null, &timestamp)
+                    .with_errors(errors.clone())
+            }
+        };
+        collections.push(clusters);
 
-                                    if !task_arn_strings.is_empty() {
-                                        let task_arn_args: Vec<&str> =
-                                            task_arn_strings.iter().map(|s| s.as_str()).collect();
-                                        let mut args = vec![
-                                            "ecs",
-                                            "describe-tasks",
-                                            "--cluster",
-                                            cluster_arn,
-                                            "--tasks",
-                                        ];
-                                        args.extend(task_arn_args);
-                                        args.push("--region");
-                                        args.push(region);
+        // Collect ECS Services, Tasks (including Fargate) and Container Instances
+        let cluster_resources = [
+            (
+                "services",
+                ClusterResource {
+                    list_operation: "list-services",
+                    list_key: "serviceArns",
+                    describe_operation: "describe-services",
+                    describe_flag: "--services",
+                    result_key: "services",
+                    batch_size: 10,
+                },
+            ),
+            (
+                "tasks",
+                ClusterResource {
+                    list_operation: "list-tasks",
+                    list_key: "taskArns",
+                    describe_operation: "describe-tasks",
+                    describe_flag: "--tasks",
+                    result_key: "tasks",
+                    batch_size: 100,
+                },
+            ),
+            (
+                "container-instances",
+                ClusterResource {
+                    list_operation: "list-container-instances",
+                    list_key: "containerInstanceArns",
+                    describe_operation: "describe-container-instances",
+                    describe_flag: "--container-instances",
+                    result_key: "containerInstances",
+                    batch_size: 100,
+                },
+            ),
+        ];
 
-                                        if let Ok(tasks) = cli.execute(&args).await {
-                                            if let Some(task_list) =
-                                                tasks.get("tasks").and_then(|t| t.as_array())
-                                            {
-                                                all_tasks.extend(task_list.clone());
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+        for (resource_type, resource) in &cluster_resources {
+            let collection = match &cluster_list {
+                Ok(cluster_arns) => {
+                    let mut errors = Vec::new();
+                    let items =
+                        collect_per_cluster(cli, region, cluster_arns, resource, &mut errors).await;
+                    ResourceCollection::new(
+                        "ecs",
+                        region,
+                        resource_type,
+                        serde_json::json!({ resource.result_key: items }),
+                        &timestamp,
+                    )
+                    .with_errors(errors)
                 }
-
-                if !all_tasks.is_empty() {
-                    collections.push(ResourceCollection {
-                        service: "ecs".to_string(),
-                        region: region.to_string(),
-                        resource_type: "tasks".to_string(),
-                        resources: serde_json::json!({ "tasks": all_tasks }),
-                        collected_at: timestamp.clone(),
-                    });
+                Err(errors) => {
+                    ResourceCollection::new("ecs", region, resource_type, Value::Null, &timestamp)
+                        .with_errors(errors.clone())
                 }
-            }
+            };
+            collections.push(collection);
         }
 
-        // Collect ECS Container Instances
-        if let Ok(cluster_arns) = cli
-            .execute(&["ecs", "list-clusters", "--region", region])
-            .await
-        {
-            if let Some(arns) = cluster_arns.get("clusterArns").and_then(|a| a.as_array()) {
-                let mut all_container_instances = Vec::new();
+        Ok(collections)
+    }
 
-                for arn in arns {
-                    if let Some(cluster_arn) = arn.as_str() {
-                        if let Ok(container_instances) = cli
-                            .execute(&[
-                                "ecs",
-                                "list-container-instances",
-                                "--cluster",
-                                cluster_arn,
-                                "--region",
-                                region,
-                            ])
-                            .await
-                        {
-                            if let Some(ci_arns) = container_instances
-                                .get("containerInstanceArns")
-                                .and_then(|a| a.as_array())
-                            {
-                                if !ci_arns.is_empty() {
-                                    let ci_arn_strings: Vec<String> = ci_arns
-                                        .iter()
-                                        .filter_map(|a| a.as_str().map(String::from))
-                                        .collect();
+    fn operations(&self) -> Vec<CliOperation> {
+        [
+            ("ecs", "list-clusters"),
+            ("ecs", "describe-clusters"),
+            ("ecs", "describe-capacity-providers"),
+            ("ecs", "list-task-definitions"),
+            ("ecs", "list-services"),
+            ("ecs", "describe-services"),
+            ("ecs", "list-tasks"),
+            ("ecs", "describe-tasks"),
+            ("ecs", "list-container-instances"),
+            ("ecs", "describe-container-instances"),
+        ]
+        .into_iter()
+        .map(|(service, operation)| CliOperation::new(service, operation))
+        .collect()
+    }
+}
 
-                                    if !ci_arn_strings.is_empty() {
-                                        let ci_arn_args: Vec<&str> =
-                                            ci_arn_strings.iter().map(|s| s.as_str()).collect();
-                                        let mut args = vec![
-                                            "ecs",
-                                            "describe-container-instances",
-                                            "--cluster",
-                                            cluster_arn,
-                                            "--container-instances",
-                                        ];
-                                        args.extend(ci_arn_args);
-                                        args.push("--region");
-                                        args.push(region);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::executor::FakeExecutor;
+    use serde_json::json;
+    use std::sync::Arc;
 
-                                        if let Ok(instances) = cli.execute(&args).await {
-                                            if let Some(inst_list) = instances
-                                                .get("containerInstances")
-                                                .and_then(|i| i.as_array())
-                                            {
-                                                all_container_instances.extend(inst_list.clone());
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+    #[tokio::test]
+    async fn test_clusters_listed_once_and_describes_batched() {
+        let service_arns: Vec<String> = (0..25).map(|i| format!("svc-{}", i)).collect();
+        let fake = Arc::new(
+            FakeExecutor::with_default(json!({}))
+                .respond(
+                    &["ecs", "list-clusters", "--region", "us-east-1"],
+                    json!({ "clusterArns": ["c"] }),
+                )
+                .respond(
+                    &[
+                        "ecs",
+                        "list-services",
+                        "--cluster",
+                        "c",
+                        "--region",
+                        "us-east-1",
+                    ],
+                    json!({ "serviceArns": service_arns }),
+                ),
+        );
+        let cli = AwsCli::with_executor("default".to_string(), fake.clone());
 
-                if !all_container_instances.is_empty() {
-                    collections.push(ResourceCollection {
-                        service: "ecs".to_string(),
-                        region: region.to_string(),
-                        resource_type: "container-instances".to_string(),
-                        resources: serde_json::json!({ "containerInstances": all_container_instances }),
-                        collected_at: timestamp,
-                    });
-                }
-            }
-        }
+        EcsCollector.collect(&cli, "us-east-1").await.unwrap();
 
-        Ok(collections)
+        let batches: Vec<usize> = fake
+            .calls()
+            .iter()
+            .filter(|call| call[1] == "describe-services")
+            .map(|call| call.iter().filter(|arg| arg.starts_with("svc-")).count())
+            .collect();
+        assert_eq!(batches, vec![10, 10, 5]);
+
+        let cluster_lists = fake
+            .calls()
+            .iter()
+            .filter(|call| call[1] == "list-clusters")
+            .count();
+        assert_eq!(cluster_lists, 1);
     }
 }
@@ -1,7 +1,8 @@
 // EventBridge resource collector
 use super::ResourceCollector;
 use crate::aws::cli::AwsCli;
-use crate::models::ResourceCollection;
+use crate::aws::permissions::CliOperation;
+use crate::models::{CollectionError, ResourceCollection};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::json;
@@ -15,146 +16,170 @@ impl ResourceCollector for EventBridgeCollector {
         let timestamp = chrono::Utc::now().to_rfc3339();
 
         // Collect event buses with detailed information
-        if let Ok(buses_response) = cli
-            .execute(&["events", "list-event-buses", "--region", region])
-            .await
-        {
-            let mut detailed_buses = Vec::new();
-
-            if let Some(buses) = buses_response.get("EventBuses").and_then(|b| b.as_array()) {
-                for bus in buses {
-                    if let Some(bus_name) = bus.get("Name").and_then(|n| n.as_str()) {
-                        let mut bus_details = bus.clone();
-
-                        // Get rules for this event bus
-                        if let Ok(rules_response) = cli
-                            .execute(&[
+        let args = ["events", "list-event-buses", "--region", region];
+        match cli.execute(&args).await {
+            Ok(buses_response) => {
+                let mut detailed_buses = Vec::new();
+                let mut errors = Vec::new();
+
+                if let Some(buses) = buses_response.get("EventBuses").and_then(|b| b.as_array()) {
+                    for bus in buses {
+                        if let Some(bus_name) = bus.get("Name").and_then(|n| n.as_str()) {
+                            let mut bus_details = bus.clone();
+
+                            // Get rules for this event bus
+                            let rules_args = [
                                 "events",
                                 "list-rules",
                                 "--event-bus-name",
                                 bus_name,
                                 "--region",
                                 region,
-                            ])
-                            .await
-                        {
-                            // Get targets for each rule
-                            let mut detailed_rules = Vec::new();
-
-                            if let Some(rules) =
-                                rules_response.get("Rules").and_then(|r| r.as_array())
-                            {
-                                for rule in rules {
-                                    if let Some(rule_name) =
-                                        rule.get("Name").and_then(|n| n.as_str())
+                            ];
+                            match cli.execute(&rules_args).await {
+                                Ok(rules_response) => {
+                                    // Get targets for each rule
+                                    let mut detailed_rules = Vec::new();
+
+                                    if let Some(rules) =
+                                        rules_response.get("Rules").and_then(|r| r.as_array())
                                     {
-                                        let mut rule_details = rule.clone();
-
-                                        // Get targets for this rule
-                                        if let Ok(targets) = cli
-                                            .execute(&[
-                                                "events",
-                                                "list-targets-by-rule",
-                                                "--rule",
-                                                rule_name,
-                                                "--event-bus-name",
-                                                bus_name,
-                                                "--region",
-                                                region,
-                                            ])
-                                            .await
-                                        {
-                                            rule_details
-                                                .as_object_mut()
-                                                .unwrap()
-                                                .insert("Targets".to_string(), targets);
+                                        for rule in rules {
+                                            if let Some(rule_name) =
+                                                rule.get("Name").and_then(|n| n.as_str())
+                                            {
+                                                let mut rule_details = rule.clone();
+
+                                                // Get targets for this rule
+                                                let targets_args = [
+                                                    "events",
+                                                    "list-targets-by-rule",
+                                                    "--rule",
+                                                    rule_name,
+                                                    "--event-bus-name",
+                                                    bus_name,
+                                                    "--region",
+                                                    region,
+                                                ];
+                                                match cli.execute(&targets_args).await {
+                                                    Ok(targets) => {
+                                                        rule_details
+                                                            .as_object_mut()
+                                                            .unwrap()
+                                                            .insert("Targets".to_string(), targets);
+                                                    }
+                                                    Err(e) => errors.extend(
+                                                        CollectionError::from_failure(
+                                                            &targets_args,
+                                                            &e,
+                                                        ),
+                                                    ),
+                                                }
+
+                                                detailed_rules.push(rule_details);
+                                            }
                                         }
-
-                                        detailed_rules.push(rule_details);
                                     }
+
+                                    bus_details.as_object_mut().unwrap().insert(
+                                        "Rules".to_string(),
+                                        json!({ "Rules": detailed_rules }),
+                                    );
+                                }
+                                Err(e) => {
+                                    errors.extend(CollectionError::from_failure(&rules_args, &e))
                                 }
                             }
 
-                            bus_details
-                                .as_object_mut()
-                                .unwrap()
-                                .insert("Rules".to_string(), json!({ "Rules": detailed_rules }));
+                            detailed_buses.push(bus_details);
                         }
-
-                        detailed_buses.push(bus_details);
                     }
                 }
-            }
 
-            let detailed_response = json!({
-                "EventBuses": detailed_buses
-            });
-
-            collections.push(ResourceCollection {
-                service: "eventbridge".to_string(),
-                region: region.to_string(),
-                resource_type: "event-buses".to_string(),
-                resources: detailed_response,
-                collected_at: timestamp.clone(),
-            });
+                let detailed_response = json!({
+                    "EventBuses": detailed_buses
+                });
+
+                collections.push(
+                    ResourceCollection::new(
+                        "eventbridge",
+                        region,
+                        "event-buses",
+                        detailed_response,
+                        &timestamp,
+                    )
+                    .with_errors(errors),
+                );
+            }
+            Err(e) => collections.push(ResourceCollection::from_result(
+                "eventbridge",
+                region,
+                "event-buses",
+                &args,
+                Err(e),
+                &timestamp,
+            )),
         }
 
         // Collect event archives
-        if let Ok(archives) = cli
-            .execute(&["events", "list-archives", "--region", region])
-            .await
-        {
-            collections.push(ResourceCollection {
-                service: "eventbridge".to_string(),
-                region: region.to_string(),
-                resource_type: "archives".to_string(),
-                resources: archives,
-                collected_at: timestamp.clone(),
-            });
-        }
+        let args = ["events", "list-archives", "--region", region];
+        collections.push(ResourceCollection::from_result(
+            "eventbridge",
+            region,
+            "archives",
+            &args,
+            cli.execute(&args).await,
+            &timestamp,
+        ));
 
         // Collect API destinations
-        if let Ok(api_destinations) = cli
-            .execute(&["events", "list-api-destinations", "--region", region])
-            .await
-        {
-            collections.push(ResourceCollection {
-                service: "eventbridge".to_string(),
-                region: region.to_string(),
-                resource_type: "api-destinations".to_string(),
-                resources: api_destinations,
-                collected_at: timestamp.clone(),
-            });
-        }
+        let args = ["events", "list-api-destinations", "--region", region];
+        collections.push(ResourceCollection::from_result(
+            "eventbridge",
+            region,
+            "api-destinations",
+            &args,
+            cli.execute(&args).await,
+            &timestamp,
+        ));
 
         // Collect connections
-        if let Ok(connections) = cli
-            .execute(&["events", "list-connections", "--region", region])
-            .await
-        {
-            collections.push(ResourceCollection {
-                service: "eventbridge".to_string(),
-                region: region.to_string(),
-                resource_type: "connections".to_string(),
-                resources: connections,
-                collected_at: timestamp.clone(),
-            });
-        }
+        let args = ["events", "list-connections", "--region", region];
+        collections.push(ResourceCollection::from_result(
+            "eventbridge",
+            region,
+            "connections",
+            &args,
+            cli.execute(&args).await,
+            &timestamp,
+        ));
 
         // Collect replays
-        if let Ok(replays) = cli
-            .execute(&["events", "list-replays", "--region", region])
-            .await
-        {
-            collections.push(ResourceCollection {
-                service: "eventbridge".to_string(),
-                region: region.to_string(),
-                resource_type: "replays".to_string(),
-                resources: replays,
-                collected_at: timestamp,
-            });
-        }
+        let args = ["events", "list-replays", "--region", region];
+        collections.push(ResourceCollection::from_result(
+            "eventbridge",
+            region,
+            "replays",
+            &args,
+            cli.execute(&args).await,
+            &timestamp,
+        ));
 
         Ok(collections)
     }
+
+    fn operations(&self) -> Vec<CliOperation> {
+        [
+            ("events", "list-event-buses"),
+            ("events", "list-rules"),
+            ("events", "list-targets-by-rule"),
+            ("events", "list-archives"),
+            ("events", "list-api-destinations"),
+            ("events", "list-connections"),
+            ("events", "list-replays"),
+        ]
+        .into_iter()
+        .map(|(service, operation)| CliOperation::new(service, operation))
+        .collect()
+    }
 }
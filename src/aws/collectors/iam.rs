@@ -2,15 +2,15 @@
 use super::ResourceCollector;
 use crate::aws::cli::AwsCli;
 use crate::aws::collector_builder::{CollectorBuilder, RegionMode};
+use crate::aws::permissions::CliOperation;
 use crate::models::ResourceCollection;
 use anyhow::Result;
 use async_trait::async_trait;
 
 pub struct IamCollector;
 
-#[async_trait]
-impl ResourceCollector for IamCollector {
-    async fn collect(&self, cli: &AwsCli, _region: &str) -> Result<Vec<ResourceCollection>> {
+impl IamCollector {
+    fn builder() -> CollectorBuilder {
         // Note: IAM is a global service, region parameter is ignored
 
         CollectorBuilder::new("iam", RegionMode::Global)
@@ -28,7 +28,16 @@ impl ResourceCollector for IamCollector {
                 "password-policy",
                 vec!["iam", "get-account-password-policy"],
             )
-            .collect(cli)
-            .await
+    }
+}
+
+#[async_trait]
+impl ResourceCollector for IamCollector {
+    async fn collect(&self, cli: &AwsCli, _region: &str) -> Result<Vec<ResourceCollection>> {
+        Self::builder().collect(cli).await
+    }
+
+    fn operations(&self) -> Vec<CliOperation> {
+        Self::builder().operations()
     }
 }
@@ -2,15 +2,15 @@
 use super::ResourceCollector;
 use crate::aws::cli::AwsCli;
 use crate::aws::collector_builder::{CollectorBuilder, DetailTemplate, RegionMode};
+use crate::aws::permissions::CliOperation;
 use crate::models::ResourceCollection;
 use anyhow::Result;
 use async_trait::async_trait;
 
 pub struct LambdaCollector;
 
-#[async_trait]
-impl ResourceCollector for LambdaCollector {
-    async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>> {
+impl LambdaCollector {
+    fn builder() -> CollectorBuilder {
         CollectorBuilder::new("lambda", RegionMode::Regional)
             // Detailed collection for functions
             .add_detailed_resource(
@@ -59,7 +59,16 @@ impl ResourceCollector for LambdaCollector {
                 "code-signing-configs",
                 vec!["lambda", "list-code-signing-configs"],
             )
-            .collect_with_region(cli, region)
-            .await
+    }
+}
+
+#[async_trait]
+impl ResourceCollector for LambdaCollector {
+    async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>> {
+        Self::builder().collect_with_region(cli, region).await
+    }
+
+    fn operations(&self) -> Vec<CliOperation> {
+        Self::builder().operations()
     }
 }
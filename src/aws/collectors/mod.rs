@@ -22,6 +22,7 @@ pub mod vpc;
 pub mod waf;
 
 use crate::aws::cli::AwsCli;
+use crate::aws::permissions::CliOperation;
 use crate::models::ResourceCollection;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -187,6 +188,9 @@ impl ServiceType {
 #[async_trait]
 pub trait ResourceCollector: Send + Sync {
     async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>>;
+
+    /// AWS CLI operations the collector may call, used to derive the IAM policy
+    fn operations(&self) -> Vec<CliOperation>;
 }
 
 // Factory function to get collector for a service type
@@ -223,3 +227,76 @@ pub fn get_all_services() -> Vec<String> {
         .map(|s| s.as_str().to_string())
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::executor::FakeExecutor;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_every_collector_runs_against_fake_executor() {
+        for service_type in ServiceType::all() {
+            let fake = Arc::new(FakeExecutor::with_default(serde_json::json!({})));
+            let cli = AwsCli::with_executor("default".to_string(), fake.clone());
+
+            let result = get_collector(service_type).collect(&cli, "us-east-1").await;
+
+            assert!(result.is_ok(), "{} collector failed", service_type.as_str());
+            assert!(
+                !fake.calls().is_empty(),
+                "{} collector issued no commands",
+                service_type.as_str()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_issued_operation_is_declared() {
+        // One item carrying every identifier the collectors look up, so that
+        // detail and per-resource commands are issued too
+        let item = serde_json::json!({
+            "Name": "x",
+            "Id": "x",
+            "FunctionName": "x",
+            "logGroupName": "x",
+            "repositoryName": "x",
+            "TopicArn": "x",
+            "CertificateArn": "x",
+            "LoadBalancerArn": "x",
+            "TargetGroupArn": "x",
+            "ListenerArn": "x",
+            "StackName": "x",
+        });
+        let response = serde_json::json!({
+            "Buckets": [item], "Functions": [item], "logGroups": [item],
+            "repositories": [item], "Topics": [item], "HostedZones": [item],
+            "EventBuses": [item], "Rules": [item], "CertificateSummaryList": [item],
+            "Certificate": item, "LoadBalancers": [item], "TargetGroups": [item],
+            "Listeners": [item], "Stacks": [item],
+            "DistributionList": { "Items": [item] },
+            "QueueUrls": ["https://sqs.us-east-1.amazonaws.com/123456789012/q"],
+            "clusterArns": ["c"], "serviceArns": ["s"], "taskArns": ["t"],
+            "containerInstanceArns": ["i"],
+        });
+
+        for service_type in ServiceType::all() {
+            let fake = Arc::new(FakeExecutor::with_default(response.clone()));
+            let cli = AwsCli::with_executor("default".to_string(), fake.clone());
+            let collector = get_collector(service_type);
+
+            collector.collect(&cli, "us-east-1").await.unwrap();
+
+            let declared = collector.operations();
+            for call in fake.calls() {
+                let operation = CliOperation::from_args(&call).unwrap();
+                assert!(
+                    declared.contains(&operation),
+                    "{} collector calls undeclared operation {:?}",
+                    service_type.as_str(),
+                    operation
+                );
+            }
+        }
+    }
+}
@@ -2,15 +2,15 @@
 use super::ResourceCollector;
 use crate::aws::cli::AwsCli;
 use crate::aws::collector_builder::{CollectorBuilder, DetailTemplate, RegionMode};
+use crate::aws::permissions::CliOperation;
 use crate::models::ResourceCollection;
 use anyhow::Result;
 use async_trait::async_trait;
 
 pub struct S3Collector;
 
-#[async_trait]
-impl ResourceCollector for S3Collector {
-    async fn collect(&self, cli: &AwsCli, _region: &str) -> Result<Vec<ResourceCollection>> {
+impl S3Collector {
+    fn builder() -> CollectorBuilder {
         // S3 is global, but we use us-east-1 for API calls
 
         CollectorBuilder::new("s3", RegionMode::Custom("us-east-1".to_string()))
@@ -86,7 +86,16 @@ impl ResourceCollector for S3Collector {
                     ),
                 ],
             )
-            .collect(cli)
-            .await
+    }
+}
+
+#[async_trait]
+impl ResourceCollector for S3Collector {
+    async fn collect(&self, cli: &AwsCli, _region: &str) -> Result<Vec<ResourceCollection>> {
+        Self::builder().collect(cli).await
+    }
+
+    fn operations(&self) -> Vec<CliOperation> {
+        Self::builder().operations()
     }
 }
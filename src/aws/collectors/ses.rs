@@ -2,15 +2,15 @@
 use super::ResourceCollector;
 use crate::aws::cli::AwsCli;
 use crate::aws::collector_builder::{CollectorBuilder, RegionMode};
+use crate::aws::permissions::CliOperation;
 use crate::models::ResourceCollection;
 use anyhow::Result;
 use async_trait::async_trait;
 
 pub struct SesCollector;
 
-#[async_trait]
-impl ResourceCollector for SesCollector {
-    async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>> {
+impl SesCollector {
+    fn builder() -> CollectorBuilder {
         CollectorBuilder::new("ses", RegionMode::Regional)
             .add_simple_list("identities", vec!["ses", "list-identities"])
             .add_simple_list("configuration-sets", vec!["ses", "list-configuration-sets"])
@@ -20,7 +20,16 @@ impl ResourceCollector for SesCollector {
                 "custom-verification-email-templates",
                 vec!["ses", "list-custom-verification-email-templates"],
             )
-            .collect_with_region(cli, region)
-            .await
+    }
+}
+
+#[async_trait]
+impl ResourceCollector for SesCollector {
+    async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>> {
+        Self::builder().collect_with_region(cli, region).await
+    }
+
+    fn operations(&self) -> Vec<CliOperation> {
+        Self::builder().operations()
     }
 }
@@ -2,15 +2,15 @@
 use super::ResourceCollector;
 use crate::aws::cli::AwsCli;
 use crate::aws::collector_builder::{CollectorBuilder, DetailTemplate, RegionMode};
+use crate::aws::permissions::CliOperation;
 use crate::models::ResourceCollection;
 use anyhow::Result;
 use async_trait::async_trait;
 
 pub struct SnsCollector;
 
-#[async_trait]
-impl ResourceCollector for SnsCollector {
-    async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>> {
+impl SnsCollector {
+    fn builder() -> CollectorBuilder {
         CollectorBuilder::new("sns", RegionMode::Regional)
             // Detailed collection for topics
             .add_detailed_resource(
@@ -35,7 +35,16 @@ impl ResourceCollector for SnsCollector {
                 "platform-applications",
                 vec!["sns", "list-platform-applications"],
             )
-            .collect_with_region(cli, region)
-            .await
+    }
+}
+
+#[async_trait]
+impl ResourceCollector for SnsCollector {
+    async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>> {
+        Self::builder().collect_with_region(cli, region).await
+    }
+
+    fn operations(&self) -> Vec<CliOperation> {
+        Self::builder().operations()
     }
 }
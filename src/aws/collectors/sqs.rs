@@ -1,7 +1,8 @@
 // SQS (Simple Queue Service) resource collector
 use super::ResourceCollector;
 use crate::aws::cli::AwsCli;
-use crate::models::ResourceCollection;
+use crate::aws::permissions::CliOperation;
+use crate::models::{CollectionError, ResourceCollection};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::json;
@@ -15,22 +16,23 @@ impl ResourceCollector for SqsCollector {
         let timestamp = chrono::Utc::now().to_rfc3339();
 
         // Collect SQS queues with detailed information
-        if let Ok(queues_response) = cli
-            .execute(&["sqs", "list-queues", "--region", region])
-            .await
-        {
-            let mut detailed_queues = Vec::new();
+        let args = ["sqs", "list-queues", "--region", region];
+        match cli.execute(&args).await {
+            Ok(queues_response) => {
+                let mut detailed_queues = Vec::new();
+                let mut errors = Vec::new();
 
-            if let Some(queue_urls) = queues_response.get("QueueUrls").and_then(|q| q.as_array()) {
-                for queue_url_value in queue_urls {
-                    if let Some(queue_url) = queue_url_value.as_str() {
-                        let mut queue_details = json!({
-                            "QueueUrl": queue_url
-                        });
+                if let Some(queue_urls) =
+                    queues_response.get("QueueUrls").and_then(|q| q.as_array())
+                {
+                    for queue_url_value in queue_urls {
+                        if let Some(queue_url) = queue_url_value.as_str() {
+                            let mut queue_details = json!({
+                                "QueueUrl": queue_url
+                            });
 
-                        // Get queue attributes
-                        if let Ok(attributes) = cli
-                            .execute(&[
+                            // Get queue attributes
+                            let attributes_args = [
                                 "sqs",
                                 "get-queue-attributes",
                                 "--queue-url",
@@ -39,47 +41,76 @@ impl ResourceCollector for SqsCollector {
                                 "All",
                                 "--region",
                                 region,
-                            ])
-                            .await
-                        {
-                            queue_details
-                                .as_object_mut()
-                                .unwrap()
-                                .insert("Attributes".to_string(), attributes);
-                        }
+                            ];
+                            match cli.execute(&attributes_args).await {
+                                Ok(attributes) => {
+                                    queue_details
+                                        .as_object_mut()
+                                        .unwrap()
+                                        .insert("Attributes".to_string(), attributes);
+                                }
+                                Err(e) => errors
+                                    .extend(CollectionError::from_failure(&attributes_args, &e)),
+                            }
 
-                        // Get tags
-                        if let Ok(tags) = cli
-                            .execute(&[
+                            // Get tags
+                            let tags_args = [
                                 "sqs",
                                 "list-queue-tags",
                                 "--queue-url",
                                 queue_url,
                                 "--region",
                                 region,
-                            ])
-                            .await
-                        {
-                            queue_details
-                                .as_object_mut()
-                                .unwrap()
-                                .insert("Tags".to_string(), tags);
-                        }
+                            ];
+                            match cli.execute(&tags_args).await {
+                                Ok(tags) => {
+                                    queue_details
+                                        .as_object_mut()
+                                        .unwrap()
+                                        .insert("Tags".to_string(), tags);
+                                }
+                                Err(e) => {
+                                    errors.extend(CollectionError::from_failure(&tags_args, &e))
+                                }
+                            }
 
-                        detailed_queues.push(queue_details);
+                            detailed_queues.push(queue_details);
+                        }
                     }
                 }
-            }
 
-            collections.push(ResourceCollection {
-                service: "sqs".to_string(),
-                region: region.to_string(),
-                resource_type: "queues".to_string(),
-                resources: json!({ "Queues": detailed_queues }),
-                collected_at: timestamp,
-            });
+                collections.push(
+                    ResourceCollection::new(
+                        "sqs",
+                        region,
+                        "queues",
+                        json!({ "Queues": detailed_queues }),
+                        &timestamp,
+                    )
+                    .with_errors(errors),
+                );
+            }
+            Err(e) => collections.push(ResourceCollection::from_result(
+                "sqs",
+                region,
+                "queues",
+                &args,
+                Err(e),
+                &timestamp,
+            )),
         }
 
         Ok(collections)
     }
+
+    fn operations(&self) -> Vec<CliOperation> {
+        [
+            ("sqs", "list-queues"),
+            ("sqs", "get-queue-attributes"),
+            ("sqs", "list-queue-tags"),
+        ]
+        .into_iter()
+        .map(|(service, operation)| CliOperation::new(service, operation))
+        .collect()
+    }
 }
@@ -2,15 +2,15 @@
 use super::ResourceCollector;
 use crate::aws::cli::AwsCli;
 use crate::aws::collector_builder::{CollectorBuilder, RegionMode};
+use crate::aws::permissions::CliOperation;
 use crate::models::ResourceCollection;
 use anyhow::Result;
 use async_trait::async_trait;
 
 pub struct WafCollector;
 
-#[async_trait]
-impl ResourceCollector for WafCollector {
-    async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>> {
+impl WafCollector {
+    fn builder(region: &str) -> CollectorBuilder {
         let mut builder = CollectorBuilder::new("waf", RegionMode::Regional)
             .add_simple_list(
                 "web-acls-regional",
@@ -37,6 +37,18 @@ impl ResourceCollector for WafCollector {
             );
         }
 
-        builder.collect_with_region(cli, region).await
+        builder
+    }
+}
+
+#[async_trait]
+impl ResourceCollector for WafCollector {
+    async fn collect(&self, cli: &AwsCli, region: &str) -> Result<Vec<ResourceCollection>> {
+        Self::builder(region).collect_with_region(cli, region).await
+    }
+
+    fn operations(&self) -> Vec<CliOperation> {
+        // us-east-1 is the superset including CloudFront-scoped ACLs
+        Self::builder("us-east-1").operations()
     }
 }
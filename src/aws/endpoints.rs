@@ -0,0 +1,92 @@
+// Custom service endpoints
+//
+// `--endpoint-url` points the collector at an AWS emulator such as LocalStack
+// or moto, either for every service or per AWS CLI service
+// (`--endpoint-url s3api=http://localhost:9000`).
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Endpoint URLs passed to the AWS CLI as `--endpoint-url`
+#[derive(Debug, Clone, Default)]
+pub struct Endpoints {
+    /// Endpoint of every service without an override
+    default: Option<String>,
+    /// Endpoint per CLI service name, e.g. `s3api`
+    services: HashMap<String, String>,
+}
+
+impl Endpoints {
+    /// Parse `--endpoint-url` values: `URL` or `SERVICE=URL`
+    pub fn parse(specs: &[String]) -> Result<Self> {
+        let mut endpoints = Self::default();
+
+        for spec in specs {
+            let spec = spec.trim();
+            match spec.split_once('=') {
+                // A service name never contains URL characters, while a URL
+                // may contain `=` in its query string
+                Some((service, url)) if !service.contains([':', '/']) => {
+                    endpoints
+                        .services
+                        .insert(service.trim().to_string(), validate_url(url.trim())?);
+                }
+                _ => {
+                    if endpoints.default.is_some() {
+                        anyhow::bail!("--endpoint-url without a service may only be given once");
+                    }
+                    endpoints.default = Some(validate_url(spec)?);
+                }
+            }
+        }
+
+        Ok(endpoints)
+    }
+
+    /// Endpoint for a CLI service, if one is configured
+    pub fn for_service(&self, service: &str) -> Option<&str> {
+        self.services
+            .get(service)
+            .or(self.default.as_ref())
+            .map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.default.is_none() && self.services.is_empty()
+    }
+}
+
+fn validate_url(url: &str) -> Result<String> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        anyhow::bail!(
+            "Invalid endpoint URL '{}': expected http:// or https://",
+            url
+        );
+    }
+    Ok(url.trim_end_matches('/').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_global_and_service_endpoints() {
+        let endpoints = Endpoints::parse(&[
+            "http://localhost:4566/".to_string(),
+            "s3api=http://localhost:9000".to_string(),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            endpoints.for_service("s3api"),
+            Some("http://localhost:9000")
+        );
+        assert_eq!(endpoints.for_service("ec2"), Some("http://localhost:4566"));
+
+        let only_s3 = Endpoints::parse(&["s3api=http://localhost:9000".to_string()]).unwrap();
+        assert_eq!(only_s3.for_service("ec2"), None);
+
+        assert!(Endpoints::parse(&["localhost:4566".to_string()]).is_err());
+    }
+}
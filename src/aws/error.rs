@@ -0,0 +1,332 @@
+// Typed AWS CLI errors
+//
+// Failed invocations are parsed into an `AwsError` carrying the AWS error code
+// (e.g. `AccessDenied`, `NoSuchCORSConfiguration`) and a coarse `AwsErrorKind`,
+// so callers can tell a missing configuration apart from a missing permission.
+
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+
+use super::executor::CliOutput;
+
+/// Exit code of the AWS CLI v2 for invalid command syntax or parameters
+const CLI_USAGE_ERROR_EXIT_CODE: i32 = 252;
+
+const ACCESS_DENIED_CODES: &[&str] = &[
+    "AccessDenied",
+    "AccessDeniedException",
+    "UnauthorizedOperation",
+    "UnauthorizedAccess",
+    "AuthorizationError",
+    "AuthorizationErrorException",
+    "Forbidden",
+];
+
+const THROTTLING_CODES: &[&str] = &[
+    "Throttling",
+    "ThrottlingException",
+    "ThrottledException",
+    "RequestThrottled",
+    "RequestThrottledException",
+    "RequestLimitExceeded",
+    "TooManyRequestsException",
+    "ProvisionedThroughputExceededException",
+    "SlowDown",
+];
+
+const SERVICE_UNAVAILABLE_CODES: &[&str] = &[
+    "InternalError",
+    "InternalFailure",
+    "InternalServerError",
+    "ServiceUnavailable",
+    "ServiceUnavailableException",
+    "RequestTimeout",
+    "RequestTimeoutException",
+];
+
+const UNSUPPORTED_CODES: &[&str] = &[
+    "NotImplemented",
+    "NotImplementedException",
+    "UnsupportedOperation",
+    "UnsupportedOperationException",
+    "InvalidAction",
+    "UnknownOperationException",
+];
+
+/// Messages of emulators (LocalStack, moto) for operations they do not implement
+const UNSUPPORTED_MESSAGES: &[&str] = &[
+    "not yet been emulated",
+    "not yet implemented",
+    "has not been implemented",
+    "not included in your current license plan",
+    "NotImplementedError",
+];
+
+const OPT_IN_REQUIRED_CODES: &[&str] = &["OptInRequired", "SubscriptionRequiredException"];
+
+const EXPIRED_TOKEN_CODES: &[&str] = &["ExpiredToken", "ExpiredTokenException", "RequestExpired"];
+
+const INVALID_CREDENTIALS_CODES: &[&str] = &[
+    "InvalidClientTokenId",
+    "UnrecognizedClientException",
+    "SignatureDoesNotMatch",
+    "InvalidToken",
+    "AuthFailure",
+];
+
+const INVALID_PARAMETER_CODES: &[&str] = &[
+    "InvalidParameter",
+    "InvalidParameterValue",
+    "InvalidParameterValueException",
+    "InvalidParameterException",
+    "InvalidParameterCombination",
+    "InvalidInput",
+    "InvalidRequest",
+    "MissingParameter",
+    "ValidationError",
+    "ValidationException",
+];
+
+/// Coarse classification of an AWS CLI failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum AwsErrorKind {
+    /// The caller lacks the IAM permission for the operation
+    AccessDenied,
+    /// The request was throttled
+    Throttled,
+    /// The resource or configuration does not exist (`NoSuch*`, `*NotFound*`)
+    NotFound,
+    /// The account or region is not subscribed to the service
+    OptInRequired,
+    /// The service endpoint could not be reached
+    EndpointUnavailable,
+    /// A transient service-side failure or timeout
+    ServiceUnavailable,
+    /// The credentials or session token have expired
+    ExpiredToken,
+    /// The credentials are missing, malformed or not recognized
+    InvalidCredentials,
+    /// The request parameters were rejected
+    InvalidParameter,
+    /// The endpoint does not implement the operation (e.g. an AWS emulator)
+    Unsupported,
+    /// The call or collector did not finish within its timeout
+    TimedOut,
+    /// Anything else
+    Unknown,
+}
+
+impl AwsErrorKind {
+    /// Classify an AWS error code
+    fn from_code(code: &str) -> Self {
+        if ACCESS_DENIED_CODES.contains(&code) {
+            Self::AccessDenied
+        } else if THROTTLING_CODES.contains(&code) {
+            Self::Throttled
+        } else if SERVICE_UNAVAILABLE_CODES.contains(&code) {
+            Self::ServiceUnavailable
+        } else if OPT_IN_REQUIRED_CODES.contains(&code) {
+            Self::OptInRequired
+        } else if EXPIRED_TOKEN_CODES.contains(&code) {
+            Self::ExpiredToken
+        } else if INVALID_CREDENTIALS_CODES.contains(&code) {
+            Self::InvalidCredentials
+        } else if INVALID_PARAMETER_CODES.contains(&code) {
+            Self::InvalidParameter
+        } else if UNSUPPORTED_CODES.contains(&code) {
+            Self::Unsupported
+        } else if code.starts_with("NoSuch") || code.contains("NotFound") {
+            Self::NotFound
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Classify CLI output that carries no AWS error code
+    fn from_message(message: &str) -> Self {
+        if message.contains("Could not connect to the endpoint URL") {
+            Self::EndpointUnavailable
+        } else if message.contains("Read timeout on endpoint URL")
+            || message.contains("Connect timeout on endpoint URL")
+            || message.contains("Connection was closed before we received a valid response")
+        {
+            Self::ServiceUnavailable
+        } else if message.contains("has expired") {
+            Self::ExpiredToken
+        } else if message.contains("Unable to locate credentials")
+            || message.contains("could not be found")
+            || message.contains("NoCredentialsError")
+        {
+            Self::InvalidCredentials
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Kind of an error returned by `AwsCli`, `Unknown` if it is not an `AwsError`
+    pub fn of(error: &anyhow::Error) -> Self {
+        error
+            .downcast_ref::<AwsError>()
+            .map(|e| e.kind)
+            .unwrap_or(Self::Unknown)
+    }
+}
+
+impl fmt::Display for AwsErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// A failed AWS CLI invocation
+#[derive(Debug, Clone)]
+pub struct AwsError {
+    pub kind: AwsErrorKind,
+    /// AWS error code, e.g. `NoSuchCORSConfiguration`
+    pub code: Option<String>,
+    /// Error output of the CLI
+    pub message: String,
+    /// Number of attempts made before giving up
+    pub attempts: u32,
+}
+
+impl AwsError {
+    /// Parse the output of a failed invocation
+    pub fn from_output(output: &CliOutput) -> Self {
+        let message = output.stderr.trim().to_string();
+        let code = error_code(&message).map(String::from);
+        // Emulators report unimplemented operations with generic codes such
+        // as `InternalFailure`, which must not be retried as transient
+        let unsupported = UNSUPPORTED_MESSAGES.iter().any(|m| message.contains(m));
+        let kind = match &code {
+            _ if unsupported => AwsErrorKind::Unsupported,
+            Some(code) => AwsErrorKind::from_code(code),
+            None if output.exit_code == CLI_USAGE_ERROR_EXIT_CODE => AwsErrorKind::InvalidParameter,
+            None => AwsErrorKind::from_message(&message),
+        };
+
+        Self {
+            kind,
+            code,
+            message,
+            attempts: 1,
+        }
+    }
+
+    /// A call or collector abandoned after `timeout`
+    pub fn timed_out(what: &str, timeout: Duration) -> Self {
+        Self {
+            kind: AwsErrorKind::TimedOut,
+            code: None,
+            message: format!("{} did not finish within {}s", what, timeout.as_secs()),
+            attempts: 1,
+        }
+    }
+
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts;
+        self
+    }
+}
+
+impl fmt::Display for AwsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AWS CLI command failed: {}", self.message)?;
+        if self.attempts > 1 {
+            write!(f, " (after {} attempts)", self.attempts)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for AwsError {}
+
+/// Extract the error code from CLI output like
+/// `An error occurred (Throttling) when calling the ... operation: ...`
+fn error_code(stderr: &str) -> Option<&str> {
+    let start = stderr.find("An error occurred (")? + "An error occurred (".len();
+    let end = stderr[start..].find(')')? + start;
+    Some(&stderr[start..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(exit_code: i32, stderr: &str) -> AwsError {
+        AwsError::from_output(&CliOutput {
+            exit_code,
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_kind_from_error_code() {
+        let cases = [
+            ("AccessDenied", AwsErrorKind::AccessDenied),
+            ("UnauthorizedOperation", AwsErrorKind::AccessDenied),
+            ("RequestLimitExceeded", AwsErrorKind::Throttled),
+            ("NoSuchCORSConfiguration", AwsErrorKind::NotFound),
+            (
+                "ServerSideEncryptionConfigurationNotFoundError",
+                AwsErrorKind::NotFound,
+            ),
+            ("ResourceNotFoundException", AwsErrorKind::NotFound),
+            ("OptInRequired", AwsErrorKind::OptInRequired),
+            ("ExpiredToken", AwsErrorKind::ExpiredToken),
+            ("InvalidClientTokenId", AwsErrorKind::InvalidCredentials),
+            ("InvalidParameterValue", AwsErrorKind::InvalidParameter),
+            ("ServiceUnavailable", AwsErrorKind::ServiceUnavailable),
+            ("NotImplemented", AwsErrorKind::Unsupported),
+            ("SomethingElse", AwsErrorKind::Unknown),
+        ];
+
+        for (code, kind) in cases {
+            let error = parse(
+                254,
+                &format!(
+                    "\nAn error occurred ({}) when calling the GetBucketCors operation: boom\n",
+                    code
+                ),
+            );
+            assert_eq!(error.kind, kind, "{}", code);
+            assert_eq!(error.code.as_deref(), Some(code));
+        }
+    }
+
+    #[test]
+    fn test_kind_from_message() {
+        assert_eq!(
+            parse(
+                255,
+                "Could not connect to the endpoint URL: \"https://ecs.xx.amazonaws.com/\""
+            )
+            .kind,
+            AwsErrorKind::EndpointUnavailable
+        );
+        assert_eq!(
+            parse(253, "Unable to locate credentials. You can configure credentials by running \"aws configure\".").kind,
+            AwsErrorKind::InvalidCredentials
+        );
+        assert_eq!(
+            parse(254, "An error occurred (InternalFailure) when calling the ListWebACLs operation: The API for service 'wafv2' is either not included in your current license plan or has not yet been emulated by LocalStack. Please refer to https://docs.localstack.cloud/references/coverage for more details.").kind,
+            AwsErrorKind::Unsupported
+        );
+        assert_eq!(
+            parse(252, "aws: error: argument --bucket: expected one argument").kind,
+            AwsErrorKind::InvalidParameter
+        );
+    }
+
+    #[test]
+    fn test_kind_of_anyhow_error() {
+        let error: anyhow::Error = parse(254, "An error occurred (AccessDenied) when").into();
+        assert_eq!(AwsErrorKind::of(&error), AwsErrorKind::AccessDenied);
+        assert_eq!(
+            AwsErrorKind::of(&anyhow::anyhow!("plain")),
+            AwsErrorKind::Unknown
+        );
+    }
+}
@@ -0,0 +1,217 @@
+// Pluggable AWS CLI command executors
+//
+// `AwsCli` builds the argument list for every invocation and delegates the
+// actual execution to a `CommandExecutor`. The default executor spawns the
+// real `aws` binary; other implementations can serve canned responses so the
+// collectors can run without a live AWS account.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::process::Command;
+
+use super::sigv4::Credentials;
+
+/// Temporary credentials of an assumed role, used instead of the profile's
+#[derive(Debug, Clone)]
+pub struct AssumedRole {
+    pub role_arn: String,
+    pub credentials: Credentials,
+    /// Region for commands that do not pass `--region`
+    pub region: String,
+}
+
+/// A single AWS CLI invocation
+#[derive(Debug, Clone)]
+pub struct CliRequest {
+    /// AWS profile the command runs under
+    pub profile: String,
+    /// Assumed role whose credentials replace the profile's
+    pub role: Option<Arc<AssumedRole>>,
+    /// Command arguments without global flags, e.g. `["ec2", "describe-vpcs"]`
+    pub args: Vec<String>,
+}
+
+/// Raw result of an AWS CLI invocation
+#[derive(Debug, Clone)]
+pub struct CliOutput {
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CliOutput {
+    /// Whether the command exited successfully
+    pub fn success(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+/// Backend that runs AWS CLI invocations
+#[async_trait]
+pub trait CommandExecutor: Send + Sync {
+    async fn run(&self, request: &CliRequest) -> Result<CliOutput>;
+}
+
+/// Executor that spawns the `aws` binary as a subprocess
+pub struct SubprocessExecutor;
+
+#[async_trait]
+impl CommandExecutor for SubprocessExecutor {
+    async fn run(&self, request: &CliRequest) -> Result<CliOutput> {
+        let mut cmd = Command::new("aws");
+
+        // Disable pager to prevent hanging on large outputs
+        cmd.env("AWS_PAGER", "");
+
+        match &request.role {
+            // The --profile flag would take precedence over the environment,
+            // so assumed-role credentials are only passed through env vars
+            Some(role) => {
+                cmd.env_remove("AWS_PROFILE");
+                cmd.env("AWS_ACCESS_KEY_ID", &role.credentials.access_key_id);
+                cmd.env("AWS_SECRET_ACCESS_KEY", &role.credentials.secret_access_key);
+                match &role.credentials.session_token {
+                    Some(token) => cmd.env("AWS_SESSION_TOKEN", token),
+                    None => cmd.env_remove("AWS_SESSION_TOKEN"),
+                };
+                cmd.env("AWS_REGION", &role.region);
+                cmd.env("AWS_DEFAULT_REGION", &role.region);
+            }
+            // Add profile
+            None => {
+                cmd.arg("--profile").arg(&request.profile);
+            }
+        }
+
+        // Add output format
+        cmd.arg("--output").arg("json");
+
+        // Add no-cli-pager flag
+        cmd.arg("--no-cli-pager");
+
+        // Add user arguments
+        cmd.args(&request.args);
+
+        // Never wait for interactive input (MFA or SSO prompts), and kill the
+        // child if the caller gives up on it (e.g. after a timeout)
+        cmd.stdin(std::process::Stdio::null());
+        cmd.kill_on_drop(true);
+
+        let output = cmd
+            .output()
+            .await
+            .context("Failed to execute AWS CLI command")?;
+
+        Ok(CliOutput {
+            exit_code: output.status.code().unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+}
+
+/// In-memory executor serving canned responses, for tests
+#[cfg(test)]
+pub struct FakeExecutor {
+    responses: std::sync::Mutex<
+        std::collections::HashMap<Vec<String>, std::collections::VecDeque<CliOutput>>,
+    >,
+    default: Option<CliOutput>,
+    calls: std::sync::Mutex<Vec<Vec<String>>>,
+}
+
+#[cfg(test)]
+impl FakeExecutor {
+    /// Create a fake that fails every command without a canned response
+    pub fn new() -> Self {
+        Self {
+            responses: Default::default(),
+            default: None,
+            calls: Default::default(),
+        }
+    }
+
+    /// Create a fake that answers unknown commands with the given JSON
+    pub fn with_default(value: serde_json::Value) -> Self {
+        Self {
+            default: Some(CliOutput {
+                exit_code: 0,
+                stdout: value.to_string(),
+                stderr: String::new(),
+            }),
+            ..Self::new()
+        }
+    }
+
+    /// Answer `args` with a successful JSON response
+    ///
+    /// Responses registered for the same arguments are served in order; the
+    /// last one is repeated for any further calls.
+    pub fn respond(self, args: &[&str], value: serde_json::Value) -> Self {
+        self.insert(
+            args,
+            CliOutput {
+                exit_code: 0,
+                stdout: value.to_string(),
+                stderr: String::new(),
+            },
+        )
+    }
+
+    /// Answer `args` with a failed invocation writing `stderr`
+    pub fn fail(self, args: &[&str], stderr: &str) -> Self {
+        self.insert(
+            args,
+            CliOutput {
+                exit_code: 254,
+                stdout: String::new(),
+                stderr: stderr.to_string(),
+            },
+        )
+    }
+
+    /// Arguments of every invocation received so far
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn insert(self, args: &[&str], output: CliOutput) -> Self {
+        let key = args.iter().map(|s| s.to_string()).collect();
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_default()
+            .push_back(output);
+        self
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl CommandExecutor for FakeExecutor {
+    async fn run(&self, request: &CliRequest) -> Result<CliOutput> {
+        self.calls.lock().unwrap().push(request.args.clone());
+
+        let canned = self
+            .responses
+            .lock()
+            .unwrap()
+            .get_mut(&request.args)
+            .and_then(|queue| {
+                if queue.len() > 1 {
+                    queue.pop_front()
+                } else {
+                    queue.front().cloned()
+                }
+            });
+        Ok(canned
+            .or_else(|| self.default.clone())
+            .unwrap_or_else(|| CliOutput {
+                exit_code: 254,
+                stdout: String::new(),
+                stderr: format!("No canned response for: aws {}", request.args.join(" ")),
+            }))
+    }
+}
@@ -0,0 +1,708 @@
+// Resource relationship graph
+//
+// Links the normalized resources of a snapshot through the references in their
+// raw payloads: an instance names its subnet, a listener its load balancer, a
+// Lambda function its role. Each link becomes an edge `from -[relation]-> to`
+// between node keys, the basis for impact analysis such as "what depends on
+// this VPC".
+//
+// Bare IDs and names only resolve within the referencing resource's account
+// and region, so multi-account snapshots do not link across accounts through
+// colliding IDs; ARNs and DNS names resolve anywhere.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use walkdir::WalkDir;
+
+use super::normalize::select;
+use crate::models::Resource;
+
+/// Tag CloudFormation puts on the resources a stack creates
+///
+/// Stack membership is only known through this tag, as stack resources are not
+/// collected; resources of a stack that do not carry it get no `created` edge.
+const STACK_ID_TAG: &str = "aws:cloudformation:stack-id";
+
+/// A directed relationship between two resources
+///
+/// `to` is the key of a collected resource, or the raw reference (an ID, ARN
+/// or address) when the referenced resource is not part of the snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub relation: String,
+}
+
+/// A reference from one resource type to another
+struct Link {
+    relation: &'static str,
+    /// Path of the referenced IDs within the raw payload
+    path: &'static str,
+    /// Resource types the reference may point to; empty for any
+    targets: &'static [&'static str],
+    /// The edge points from the referenced resource to this one
+    reverse: bool,
+}
+
+fn link(relation: &'static str, path: &'static str, targets: &'static [&'static str]) -> Link {
+    Link {
+        relation,
+        path,
+        targets,
+        reverse: false,
+    }
+}
+
+fn reverse(relation: &'static str, path: &'static str, targets: &'static [&'static str]) -> Link {
+    Link {
+        relation,
+        path,
+        targets,
+        reverse: true,
+    }
+}
+
+const SUBNETS: &[&str] = &["subnets"];
+const VPCS: &[&str] = &["vpcs"];
+const SECURITY_GROUPS: &[&str] = &["security-groups"];
+const INSTANCES: &[&str] = &["instances"];
+const TARGET_GROUPS: &[&str] = &["target-groups"];
+
+/// References of a resource type
+fn links(service: &str, resource_type: &str) -> Vec<Link> {
+    match (service, resource_type) {
+        ("ec2", "instances") => vec![
+            link("in-subnet", "SubnetId", SUBNETS),
+            link("in-vpc", "VpcId", VPCS),
+            link(
+                "uses-security-group",
+                "SecurityGroups[].GroupId",
+                SECURITY_GROUPS,
+            ),
+            link(
+                "has-interface",
+                "NetworkInterfaces[].NetworkInterfaceId",
+                &["network-interfaces"],
+            ),
+            link(
+                "attached-volume",
+                "BlockDeviceMappings[].Ebs.VolumeId",
+                &["volumes"],
+            ),
+            link(
+                "uses-instance-profile",
+                "IamInstanceProfile.Arn",
+                &["instance-profiles"],
+            ),
+        ],
+        ("ec2" | "vpc", "subnets" | "security-groups") => vec![link("in-vpc", "VpcId", VPCS)],
+        ("ec2" | "vpc", "route-tables") => vec![
+            link("in-vpc", "VpcId", VPCS),
+            link("associated-subnet", "Associations[].SubnetId", SUBNETS),
+            link(
+                "routes-to",
+                "Routes[].GatewayId",
+                &["internet-gateways", "vpn-gateways"],
+            ),
+            link("routes-to", "Routes[].NatGatewayId", &["nat-gateways"]),
+        ],
+        ("ec2" | "vpc", "internet-gateways" | "vpn-gateways") => {
+            vec![link("attached-to", "Attachments[].VpcId", VPCS)]
+        }
+        ("ec2" | "vpc", "nat-gateways") => vec![
+            link("in-subnet", "SubnetId", SUBNETS),
+            link("in-vpc", "VpcId", VPCS),
+        ],
+        ("ec2" | "vpc", "network-acls") => vec![
+            link("in-vpc", "VpcId", VPCS),
+            link("associated-subnet", "Associations[].SubnetId", SUBNETS),
+        ],
+        ("ec2" | "vpc", "vpc-endpoints") => vec![
+            link("in-vpc", "VpcId", VPCS),
+            link("in-subnet", "SubnetIds[]", SUBNETS),
+            link("uses-security-group", "Groups[].GroupId", SECURITY_GROUPS),
+        ],
+        ("ec2" | "vpc", "vpc-peering-connections") => vec![
+            link("peers", "RequesterVpcInfo.VpcId", VPCS),
+            link("peers", "AccepterVpcInfo.VpcId", VPCS),
+        ],
+        ("ec2", "network-interfaces") => vec![
+            link("in-subnet", "SubnetId", SUBNETS),
+            link("in-vpc", "VpcId", VPCS),
+            link("uses-security-group", "Groups[].GroupId", SECURITY_GROUPS),
+        ],
+        ("ec2", "elastic-ips") => vec![
+            link("attached-to", "InstanceId", INSTANCES),
+            link("attached-to", "NetworkInterfaceId", &["network-interfaces"]),
+        ],
+        ("ec2", "transit-gateway-attachments") => vec![link("attached-to", "ResourceId", VPCS)],
+        ("ec2", "auto-scaling-groups") => vec![
+            link("has-instance", "Instances[].InstanceId", INSTANCES),
+            link("registered-in", "TargetGroupARNs[]", TARGET_GROUPS),
+        ],
+
+        ("elb", "load-balancers") => vec![
+            link("in-vpc", "VpcId", VPCS),
+            link("in-subnet", "AvailabilityZones[].SubnetId", SUBNETS),
+            link("uses-security-group", "SecurityGroups[]", SECURITY_GROUPS),
+        ],
+        ("elb", "classic-load-balancers") => vec![
+            link("in-vpc", "VPCId", VPCS),
+            link("in-subnet", "Subnets[]", SUBNETS),
+            link("uses-security-group", "SecurityGroups[]", SECURITY_GROUPS),
+            link("routes-to", "Instances[].InstanceId", INSTANCES),
+        ],
+        ("elb", "listeners") => vec![
+            reverse("has-listener", "LoadBalancerArn", &["load-balancers"]),
+            link(
+                "forwards-to",
+                "DefaultActions[].TargetGroupArn",
+                TARGET_GROUPS,
+            ),
+            link(
+                "forwards-to",
+                "DefaultActions[].ForwardConfig.TargetGroups[].TargetGroupArn",
+                TARGET_GROUPS,
+            ),
+            link(
+                "forwards-to",
+                "Rules.Rules[].Actions[].TargetGroupArn",
+                TARGET_GROUPS,
+            ),
+        ],
+        ("elb", "target-groups") => vec![
+            link("in-vpc", "VpcId", VPCS),
+            link(
+                "routes-to",
+                "TargetHealth.TargetHealthDescriptions[].Target.Id",
+                &["instances", "functions"],
+            ),
+        ],
+
+        ("lambda", "functions") => vec![
+            link("assumes-role", "Role", &["roles"]),
+            link("in-vpc", "VpcConfig.VpcId", VPCS),
+            link("in-subnet", "VpcConfig.SubnetIds[]", SUBNETS),
+            link(
+                "uses-security-group",
+                "VpcConfig.SecurityGroupIds[]",
+                SECURITY_GROUPS,
+            ),
+            link(
+                "event-source",
+                "EventSourceMappings.EventSourceMappings[].EventSourceArn",
+                &[],
+            ),
+        ],
+
+        ("ecs", "services") => vec![
+            link("in-cluster", "clusterArn", &["clusters"]),
+            link(
+                "runs-task-definition",
+                "taskDefinition",
+                &["task-definitions"],
+            ),
+            link(
+                "registered-in",
+                "loadBalancers[].targetGroupArn",
+                TARGET_GROUPS,
+            ),
+            link(
+                "in-subnet",
+                "networkConfiguration.awsvpcConfiguration.subnets[]",
+                SUBNETS,
+            ),
+            link(
+                "uses-security-group",
+                "networkConfiguration.awsvpcConfiguration.securityGroups[]",
+                SECURITY_GROUPS,
+            ),
+        ],
+        ("ecs", "tasks") => vec![
+            link("in-cluster", "clusterArn", &["clusters"]),
+            link(
+                "runs-task-definition",
+                "taskDefinitionArn",
+                &["task-definitions"],
+            ),
+        ],
+        ("ecs", "container-instances") => vec![link("runs-on", "ec2InstanceId", INSTANCES)],
+
+        ("cloudfront", "distributions") => vec![
+            link(
+                "origin",
+                "Origins.Items[].DomainName",
+                &["buckets", "load-balancers", "classic-load-balancers"],
+            ),
+            link("protected-by", "WebACLId", &["web-acls-cloudfront"]),
+            link(
+                "uses-certificate",
+                "ViewerCertificate.ACMCertificateArn",
+                &["certificates"],
+            ),
+        ],
+
+        ("rds", "db-instances") => vec![
+            link("member-of", "DBClusterIdentifier", &["db-clusters"]),
+            link("in-vpc", "DBSubnetGroup.VpcId", VPCS),
+            link(
+                "in-subnet",
+                "DBSubnetGroup.Subnets[].SubnetIdentifier",
+                SUBNETS,
+            ),
+            link(
+                "uses-security-group",
+                "VpcSecurityGroups[].VpcSecurityGroupId",
+                SECURITY_GROUPS,
+            ),
+        ],
+        ("rds", "db-clusters") => vec![link(
+            "uses-security-group",
+            "VpcSecurityGroups[].VpcSecurityGroupId",
+            SECURITY_GROUPS,
+        )],
+        ("elasticache", "cache-clusters") => vec![
+            link("member-of", "ReplicationGroupId", &["replication-groups"]),
+            link(
+                "uses-security-group",
+                "SecurityGroups[].SecurityGroupId",
+                SECURITY_GROUPS,
+            ),
+        ],
+
+        ("sns", "topics") => vec![link(
+            "delivers-to",
+            "Subscriptions.Subscriptions[].Endpoint",
+            &["queues", "functions"],
+        )],
+        ("eventbridge", "event-buses") => {
+            vec![link("targets", "Rules.Rules[].Targets.Targets[].Arn", &[])]
+        }
+
+        _ => Vec::new(),
+    }
+}
+
+/// Key of a resource in the graph: its ARN, or
+/// `service:resource_type:account:region:id`
+pub fn node_key(resource: &Resource) -> String {
+    resource.arn.clone().unwrap_or_else(|| {
+        format!(
+            "{}:{}:{}:{}:{}",
+            resource.service,
+            resource.resource_type,
+            resource.account,
+            resource.region,
+            resource.id
+        )
+    })
+}
+
+/// Resources of a snapshot and the relationships between them
+pub struct Graph {
+    pub nodes: BTreeMap<String, Resource>,
+    pub edges: BTreeSet<Edge>,
+}
+
+impl Graph {
+    /// Link the resources of a snapshot
+    ///
+    /// Resources collected by several services (the EC2 and VPC collectors
+    /// both list VPCs) appear once.
+    pub fn build(resources: Vec<Resource>) -> Self {
+        let mut nodes = BTreeMap::new();
+        for resource in resources {
+            nodes.entry(node_key(&resource)).or_insert(resource);
+        }
+
+        let index = Index::new(&nodes);
+        let mut edges = BTreeSet::new();
+        let mut edge = |from: &str, to: String, relation: &str| {
+            edges.insert(Edge {
+                from: from.to_string(),
+                to,
+                relation: relation.to_string(),
+            });
+        };
+
+        for (key, resource) in &nodes {
+            for link in links(&resource.service, &resource.resource_type) {
+                for reference in select(&resource.raw, link.path) {
+                    let Some(reference) = reference.as_str().filter(|r| !r.is_empty()) else {
+                        continue;
+                    };
+                    let target = index.resolve(reference, link.targets, resource);
+                    if link.reverse {
+                        edge(&target, key.clone(), link.relation);
+                    } else {
+                        edge(key, target, link.relation);
+                    }
+                }
+            }
+
+            // Stacks do not list their resources, but tag them
+            if let Some(stack_id) = resource.tags.get(STACK_ID_TAG) {
+                edge(
+                    &index.resolve(stack_id, &["stacks"], resource),
+                    key.clone(),
+                    "created",
+                );
+            }
+
+            if resource.service == "route53" && resource.resource_type == "hosted-zones" {
+                for (record, target, relation) in records(resource) {
+                    edge(key, record.clone(), "has-record");
+                    edge(&record, index.resolve(&target, &[], resource), relation);
+                }
+            }
+        }
+
+        Self { nodes, edges }
+    }
+
+    /// Load and link the normalized resources of a snapshot directory
+    ///
+    /// Reads every `{service}_{region}_resources.json` below `dir`, including
+    /// the per-account directories of multi-account runs. Timestamped files
+    /// written with `--create-new-file` are not read.
+    pub fn load(dir: &Path) -> Result<Self> {
+        if !dir.is_dir() {
+            anyhow::bail!("Snapshot directory {} does not exist", dir.display());
+        }
+
+        let mut resources = Vec::new();
+        for entry in WalkDir::new(dir).sort_by_file_name() {
+            let entry = entry.context(format!("Failed to read {}", dir.display()))?;
+            let is_resources_file = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.ends_with("_resources.json"));
+            if !entry.file_type().is_file() || !is_resources_file {
+                continue;
+            }
+
+            let path = entry.path();
+            let contents = std::fs::read_to_string(path)
+                .context(format!("Failed to read {}", path.display()))?;
+            let file: ResourcesFile = serde_json::from_str(&contents)
+                .context(format!("Failed to parse {}", path.display()))?;
+            resources.extend(file.resources);
+        }
+
+        if resources.is_empty() {
+            anyhow::bail!(
+                "No normalized resources found in {}; run `aws collect` first",
+                dir.display()
+            );
+        }
+
+        Ok(Self::build(resources))
+    }
+
+    /// Nodes (without their payloads) and edges, as written to `graph.json`
+    pub fn to_json(&self) -> Value {
+        let nodes: Vec<Value> = self
+            .nodes
+            .iter()
+            .map(|(key, resource)| {
+                json!({
+                    "key": key,
+                    "service": resource.service,
+                    "resource_type": resource.resource_type,
+                    "id": resource.id,
+                    "name": resource.name,
+                    "region": resource.region,
+                })
+            })
+            .collect();
+
+        json!({ "nodes": nodes, "edges": self.edges })
+    }
+}
+
+/// A `{service}_{region}_resources.json` file
+#[derive(Deserialize)]
+struct ResourcesFile {
+    resources: Vec<Resource>,
+}
+
+/// `(record, target, relation)` of the alias and CNAME records of a hosted zone
+///
+/// Records are not collected resources; they are keyed `zone-id/name type`.
+fn records(zone: &Resource) -> Vec<(String, String, &'static str)> {
+    select(&zone.raw, "RecordSets.ResourceRecordSets[]")
+        .into_iter()
+        .flat_map(|record| {
+            let key = format!(
+                "{}/{} {}",
+                zone.id,
+                record["Name"].as_str().unwrap_or_default(),
+                record["Type"].as_str().unwrap_or_default()
+            );
+            let alias = record["AliasTarget"]["DNSName"]
+                .as_str()
+                .map(|target| (key.clone(), target.to_string(), "alias-to"));
+            let values = if record["Type"] == "CNAME" {
+                select(record, "ResourceRecords[].Value")
+            } else {
+                Vec::new()
+            };
+            let cnames = values
+                .into_iter()
+                .filter_map(Value::as_str)
+                .map(move |target| (key.clone(), target.to_string(), "points-to"));
+            alias.into_iter().chain(cnames)
+        })
+        .collect()
+}
+
+/// `(node key, resource type)` of the resources a reference may point to
+type Candidates<'a> = Vec<(&'a str, &'a str)>;
+
+/// Lookup of node keys by ID, ARN and DNS name
+struct Index<'a> {
+    /// By ARN and DNS name, unique across accounts
+    global: HashMap<String, Candidates<'a>>,
+    /// By `(account, region, id)`, as IDs and names are only unique there
+    scoped: HashMap<(&'a str, &'a str, &'a str), Candidates<'a>>,
+}
+
+impl<'a> Index<'a> {
+    fn new(nodes: &'a BTreeMap<String, Resource>) -> Self {
+        let mut global: HashMap<String, Candidates> = HashMap::new();
+        let mut scoped: HashMap<(&str, &str, &str), Candidates> = HashMap::new();
+        for (key, resource) in nodes {
+            let candidate = (key.as_str(), resource.resource_type.as_str());
+            let dns_names = ["DNSName", "DomainName"]
+                .into_iter()
+                .filter_map(|field| resource.raw.get(field)?.as_str())
+                .map(normalize_dns);
+            // Bucket names are unique across accounts, and referenced from
+            // other regions by CloudFront origins
+            let bucket = (resource.service == "s3" && resource.resource_type == "buckets")
+                .then(|| resource.id.clone());
+            for reference in resource
+                .arn
+                .clone()
+                .into_iter()
+                .chain(dns_names)
+                .chain(bucket)
+            {
+                global.entry(reference).or_default().push(candidate);
+            }
+            scoped
+                .entry((&resource.account, &resource.region, &resource.id))
+                .or_default()
+                .push(candidate);
+        }
+        Self { global, scoped }
+    }
+
+    /// Key of the resource a reference of `from` points to, or the reference
+    /// itself
+    fn resolve(&self, reference: &str, targets: &[&str], from: &Resource) -> String {
+        let dns = normalize_dns(reference);
+        // `bucket.s3.amazonaws.com`, `bucket.s3.eu-west-1.amazonaws.com`...
+        let bucket = dns
+            .split_once(".s3.")
+            .or_else(|| dns.split_once(".s3-"))
+            .map(|(bucket, _)| bucket.to_string());
+
+        let by_id = self
+            .scoped
+            .get(&(from.account.as_str(), from.region.as_str(), reference));
+        [Some(reference.to_string()), Some(dns), bucket]
+            .into_iter()
+            .flatten()
+            .filter_map(|candidate| self.global.get(&candidate))
+            .chain(by_id)
+            .flatten()
+            .find(|(_, resource_type)| targets.is_empty() || targets.contains(resource_type))
+            .map(|(key, _)| key.to_string())
+            .unwrap_or_else(|| reference.to_string())
+    }
+}
+
+/// Lowercase DNS name without the trailing dot and `dualstack.` prefix
+fn normalize_dns(name: &str) -> String {
+    let name = name.trim_end_matches('.').to_ascii_lowercase();
+    match name.strip_prefix("dualstack.") {
+        Some(name) => name.to_string(),
+        None => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::normalize::normalize;
+    use crate::models::ResourceCollection;
+
+    fn resources(service: &str, resource_type: &str, payload: Value) -> Vec<Resource> {
+        let collection =
+            ResourceCollection::new(service, "us-east-1", resource_type, payload, "now");
+        normalize(&collection, "123456789012", "aws")
+    }
+
+    fn has_edge(graph: &Graph, from: &str, relation: &str, to: &str) -> bool {
+        graph.edges.contains(&Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: relation.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_build_links_network_load_balancing_and_stacks() {
+        let arn = |resource: &str| format!("arn:aws:ec2:us-east-1:123456789012:{}", resource);
+        let lb = "arn:aws:elasticloadbalancing:us-east-1:123456789012:loadbalancer/app/web/1";
+        let listener = "arn:aws:elasticloadbalancing:us-east-1:123456789012:listener/app/web/1/2";
+        let tg = "arn:aws:elasticloadbalancing:us-east-1:123456789012:targetgroup/web/3";
+        let stack = "arn:aws:cloudformation:us-east-1:123456789012:stack/web/4";
+
+        let mut all = Vec::new();
+        all.extend(resources(
+            "ec2",
+            "vpcs",
+            json!({ "Vpcs": [{ "VpcId": "vpc-1" }] }),
+        ));
+        // The VPC collector reports the same VPC again
+        all.extend(resources(
+            "vpc",
+            "vpcs",
+            json!({ "Vpcs": [{ "VpcId": "vpc-1" }] }),
+        ));
+        all.extend(resources(
+            "ec2",
+            "subnets",
+            json!({ "Subnets": [{ "SubnetId": "subnet-1", "VpcId": "vpc-1" }] }),
+        ));
+        all.extend(resources(
+            "ec2",
+            "instances",
+            json!({ "Reservations": [{ "Instances": [{
+                "InstanceId": "i-1", "SubnetId": "subnet-1", "VpcId": "vpc-1",
+                "SecurityGroups": [{ "GroupId": "sg-missing" }],
+                "Tags": [{ "Key": "aws:cloudformation:stack-id", "Value": stack }]
+            }]}]}),
+        ));
+        all.extend(resources(
+            "elb",
+            "load-balancers",
+            json!({ "LoadBalancers": [{
+                "LoadBalancerName": "web", "LoadBalancerArn": lb,
+                "DNSName": "web-1.us-east-1.elb.amazonaws.com"
+            }]}),
+        ));
+        all.extend(resources(
+            "elb",
+            "listeners",
+            json!({ "Listeners": [{
+                "ListenerArn": listener, "LoadBalancerArn": lb,
+                "DefaultActions": [{ "TargetGroupArn": tg }]
+            }]}),
+        ));
+        all.extend(resources(
+            "elb",
+            "target-groups",
+            json!({ "TargetGroups": [{
+                "TargetGroupName": "web", "TargetGroupArn": tg,
+                "TargetHealth": { "TargetHealthDescriptions": [{ "Target": { "Id": "i-1" } }] }
+            }]}),
+        ));
+        all.extend(resources(
+            "cloudformation",
+            "stacks",
+            json!({ "Stacks": [{ "StackName": "web", "StackId": stack }] }),
+        ));
+        all.extend(resources(
+            "route53",
+            "hosted-zones",
+            json!({ "HostedZones": [{
+                "Id": "/hostedzone/Z1", "Name": "example.com.",
+                "RecordSets": { "ResourceRecordSets": [{
+                    "Name": "www.example.com.", "Type": "A",
+                    "AliasTarget": { "DNSName": "dualstack.web-1.us-east-1.elb.amazonaws.com." }
+                }]}
+            }]}),
+        ));
+
+        let graph = Graph::build(all);
+        assert_eq!(graph.nodes.len(), 8);
+
+        let instance = arn("instance/i-1");
+        assert!(has_edge(
+            &graph,
+            &instance,
+            "in-subnet",
+            &arn("subnet/subnet-1")
+        ));
+        assert!(has_edge(
+            &graph,
+            &arn("subnet/subnet-1"),
+            "in-vpc",
+            &arn("vpc/vpc-1")
+        ));
+        // Unknown references keep the raw ID
+        assert!(has_edge(
+            &graph,
+            &instance,
+            "uses-security-group",
+            "sg-missing"
+        ));
+        assert!(has_edge(&graph, lb, "has-listener", listener));
+        assert!(has_edge(&graph, listener, "forwards-to", tg));
+        assert!(has_edge(&graph, tg, "routes-to", &instance));
+        assert!(has_edge(&graph, stack, "created", &instance));
+
+        let record = "/hostedzone/Z1/www.example.com. A";
+        assert!(has_edge(
+            &graph,
+            "arn:aws:route53:::hostedzone/Z1",
+            "has-record",
+            record
+        ));
+        assert!(has_edge(&graph, record, "alias-to", lb));
+
+        let json = graph.to_json();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 8);
+        assert!(json["edges"][0]["relation"].is_string());
+    }
+
+    #[test]
+    fn test_build_resolves_ids_within_account() {
+        let mut all = Vec::new();
+        for account in ["111111111111", "222222222222"] {
+            for (resource_type, payload) in [
+                ("vpcs", json!({ "Vpcs": [{ "VpcId": "vpc-1" }] })),
+                (
+                    "subnets",
+                    json!({ "Subnets": [{ "SubnetId": "subnet-1", "VpcId": "vpc-1" }] }),
+                ),
+            ] {
+                let collection =
+                    ResourceCollection::new("ec2", "us-east-1", resource_type, payload, "now");
+                all.extend(normalize(&collection, account, "aws"));
+            }
+        }
+
+        let graph = Graph::build(all);
+        assert_eq!(graph.nodes.len(), 4);
+        let arn = |account: &str, resource: &str| {
+            format!("arn:aws:ec2:us-east-1:{}:{}", account, resource)
+        };
+        for account in ["111111111111", "222222222222"] {
+            assert!(has_edge(
+                &graph,
+                &arn(account, "subnet/subnet-1"),
+                "in-vpc",
+                &arn(account, "vpc/vpc-1")
+            ));
+        }
+        assert_eq!(graph.edges.len(), 2);
+    }
+}
@@ -0,0 +1,541 @@
+// Graph export
+//
+// Renders the relationship graph of a snapshot as a Graphviz DOT digraph, a
+// Mermaid flowchart or GraphML. Resources inside a VPC can be grouped into one
+// cluster per VPC, and per subnet within it, so the diagram follows the
+// network layout of the account.
+
+use std::collections::{BTreeSet, HashMap};
+
+use super::graph::{Edge, Graph};
+use crate::models::Resource;
+
+/// Resources to include; every given criterion must match
+#[derive(Debug, Default)]
+pub struct GraphFilter {
+    /// VPC IDs the resources must be in
+    pub vpcs: Vec<String>,
+    pub regions: Vec<String>,
+    /// `(key, value)` tags the resources must all carry
+    pub tags: Vec<(String, String)>,
+    pub services: Vec<String>,
+}
+
+/// Grouping of the resources of a diagram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Clustering {
+    None,
+    Vpc,
+    /// VPCs, with a nested cluster per subnet
+    Subnet,
+}
+
+/// A node of the diagram: a collected resource or a reference to one that
+/// was not collected
+struct Node {
+    id: String,
+    label: String,
+    resource_type: Option<String>,
+    service: Option<String>,
+    region: Option<String>,
+}
+
+/// A group of nodes, e.g. a VPC
+#[derive(Default)]
+struct Cluster {
+    id: String,
+    label: String,
+    /// Indices into `Diagram::nodes`
+    nodes: Vec<usize>,
+    children: Vec<Cluster>,
+}
+
+/// `(subnet key, cluster)` of the subnets of a VPC
+type SubnetClusters<'a> = Vec<(&'a str, Cluster)>;
+
+/// The filtered, clustered graph ready to render
+pub struct Diagram {
+    nodes: Vec<Node>,
+    /// `(from, to, relation)` as indices into `nodes`
+    edges: Vec<(usize, usize, String)>,
+    /// Unlabeled root holding the nodes outside any cluster
+    root: Cluster,
+}
+
+impl Diagram {
+    pub fn new(graph: &Graph, filter: &GraphFilter, clustering: Clustering) -> Self {
+        let mut outgoing: HashMap<&str, Vec<&Edge>> = HashMap::new();
+        for edge in &graph.edges {
+            outgoing.entry(edge.from.as_str()).or_default().push(edge);
+        }
+        let network = Network {
+            graph,
+            outgoing: &outgoing,
+        };
+
+        let selected: BTreeSet<&str> = graph
+            .nodes
+            .iter()
+            .filter(|(key, resource)| filter.matches(resource, network.vpc_of(key)))
+            .map(|(key, _)| key.as_str())
+            .collect();
+
+        // Keep edges between selected resources, and edges leading from them to
+        // references that were not collected (and onwards, e.g. Route 53 records)
+        let mut included = selected.clone();
+        let mut edges: BTreeSet<&Edge> = BTreeSet::new();
+        loop {
+            let before = edges.len();
+            for edge in &graph.edges {
+                let to_included =
+                    selected.contains(edge.to.as_str()) || !graph.nodes.contains_key(&edge.to);
+                if included.contains(edge.from.as_str()) && to_included && edges.insert(edge) {
+                    included.insert(edge.to.as_str());
+                }
+            }
+            if edges.len() == before {
+                break;
+            }
+        }
+
+        let mut nodes = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for key in &included {
+            index.insert(key, nodes.len());
+            nodes.push(match graph.nodes.get(*key) {
+                Some(resource) => Node {
+                    id: format!("n{}", nodes.len()),
+                    label: format!(
+                        "{}\n{}",
+                        resource.name.as_deref().unwrap_or(&resource.id),
+                        resource.resource_type
+                    ),
+                    resource_type: Some(resource.resource_type.clone()),
+                    service: Some(resource.service.clone()),
+                    region: Some(resource.region.clone()),
+                },
+                None => Node {
+                    id: format!("n{}", nodes.len()),
+                    label: key.to_string(),
+                    resource_type: None,
+                    service: None,
+                    region: None,
+                },
+            });
+        }
+
+        let edges = edges
+            .into_iter()
+            .map(|edge| {
+                (
+                    index[edge.from.as_str()],
+                    index[edge.to.as_str()],
+                    edge.relation.clone(),
+                )
+            })
+            .collect();
+
+        let root = network.clusters(&included, &index, clustering);
+
+        Self { nodes, edges, root }
+    }
+
+    /// Graphviz DOT digraph
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph resources {\n  rankdir=LR;\n  node [shape=box];\n");
+        self.dot_cluster(&self.root, 1, &mut out);
+        for (from, to, relation) in &self.edges {
+            out.push_str(&format!(
+                "  {} -> {} [label=\"{}\"];\n",
+                self.nodes[*from].id,
+                self.nodes[*to].id,
+                dot_escape(relation)
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn dot_cluster(&self, cluster: &Cluster, depth: usize, out: &mut String) {
+        let indent = "  ".repeat(depth);
+        for child in &cluster.children {
+            out.push_str(&format!("{}subgraph cluster_{} {{\n", indent, child.id));
+            out.push_str(&format!(
+                "{}  label=\"{}\";\n",
+                indent,
+                dot_escape(&child.label)
+            ));
+            self.dot_cluster(child, depth + 1, out);
+            out.push_str(&format!("{}}}\n", indent));
+        }
+        for node in &cluster.nodes {
+            let node = &self.nodes[*node];
+            let style = if node.resource_type.is_none() {
+                ", style=dashed"
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "{}{} [label=\"{}\"{}];\n",
+                indent,
+                node.id,
+                dot_escape(&node.label),
+                style
+            ));
+        }
+    }
+
+    /// Mermaid flowchart
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart LR\n");
+        self.mermaid_cluster(&self.root, 1, &mut out);
+        for (from, to, relation) in &self.edges {
+            out.push_str(&format!(
+                "  {} -->|{}| {}\n",
+                self.nodes[*from].id,
+                mermaid_escape(relation),
+                self.nodes[*to].id
+            ));
+        }
+        out
+    }
+
+    fn mermaid_cluster(&self, cluster: &Cluster, depth: usize, out: &mut String) {
+        let indent = "  ".repeat(depth);
+        for child in &cluster.children {
+            out.push_str(&format!(
+                "{}subgraph {}[\"{}\"]\n",
+                indent,
+                child.id,
+                mermaid_escape(&child.label)
+            ));
+            self.mermaid_cluster(child, depth + 1, out);
+            out.push_str(&format!("{}end\n", indent));
+        }
+        for node in &cluster.nodes {
+            let node = &self.nodes[*node];
+            out.push_str(&format!(
+                "{}{}[\"{}\"]\n",
+                indent,
+                node.id,
+                mermaid_escape(&node.label)
+            ));
+        }
+    }
+
+    /// GraphML, with clusters as nested graphs
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"label\" for=\"all\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"service\" for=\"node\" attr.name=\"service\" attr.type=\"string\"/>\n",
+            "  <key id=\"resource_type\" for=\"node\" attr.name=\"resource_type\" attr.type=\"string\"/>\n",
+            "  <key id=\"region\" for=\"node\" attr.name=\"region\" attr.type=\"string\"/>\n",
+            "  <key id=\"relation\" for=\"edge\" attr.name=\"relation\" attr.type=\"string\"/>\n",
+            "  <graph id=\"resources\" edgedefault=\"directed\">\n",
+        ));
+        self.graphml_cluster(&self.root, 2, &mut out);
+        for (i, (from, to, relation)) in self.edges.iter().enumerate() {
+            out.push_str(&format!(
+                "    <edge id=\"e{}\" source=\"{}\" target=\"{}\"><data key=\"relation\">{}</data></edge>\n",
+                i,
+                self.nodes[*from].id,
+                self.nodes[*to].id,
+                xml_escape(relation)
+            ));
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    fn graphml_cluster(&self, cluster: &Cluster, depth: usize, out: &mut String) {
+        let indent = "  ".repeat(depth);
+        for child in &cluster.children {
+            out.push_str(&format!(
+                "{}<node id=\"{}\"><data key=\"label\">{}</data>\n",
+                indent,
+                child.id,
+                xml_escape(&child.label)
+            ));
+            out.push_str(&format!(
+                "{}  <graph id=\"{}:\" edgedefault=\"directed\">\n",
+                indent, child.id
+            ));
+            self.graphml_cluster(child, depth + 2, out);
+            out.push_str(&format!("{}  </graph>\n{}</node>\n", indent, indent));
+        }
+        for node in &cluster.nodes {
+            let node = &self.nodes[*node];
+            out.push_str(&format!(
+                "{}<node id=\"{}\"><data key=\"label\">{}</data>",
+                indent,
+                node.id,
+                xml_escape(&node.label)
+            ));
+            let attributes = [
+                ("service", &node.service),
+                ("resource_type", &node.resource_type),
+                ("region", &node.region),
+            ];
+            for (key, value) in attributes {
+                if let Some(value) = value {
+                    out.push_str(&format!(
+                        "<data key=\"{}\">{}</data>",
+                        key,
+                        xml_escape(value)
+                    ));
+                }
+            }
+            out.push_str("</node>\n");
+        }
+    }
+}
+
+impl GraphFilter {
+    fn matches(&self, resource: &Resource, vpc: Option<&Resource>) -> bool {
+        let vpc_matches =
+            self.vpcs.is_empty() || vpc.is_some_and(|vpc| self.vpcs.contains(&vpc.id));
+        let tags_match = self
+            .tags
+            .iter()
+            .all(|(key, value)| resource.tags.get(key) == Some(value));
+
+        vpc_matches
+            && tags_match
+            && (self.regions.is_empty() || self.regions.contains(&resource.region))
+            && (self.services.is_empty() || self.services.contains(&resource.service))
+    }
+}
+
+/// VPC and subnet membership of the resources of a graph
+struct Network<'a> {
+    graph: &'a Graph,
+    outgoing: &'a HashMap<&'a str, Vec<&'a Edge>>,
+}
+
+impl<'a> Network<'a> {
+    /// Key of the resource of type `resource_type` that `key` points to with
+    /// one of `relations`
+    fn container(&self, key: &str, relations: &[&str], resource_type: &str) -> Option<&'a str> {
+        self.outgoing
+            .get(key)?
+            .iter()
+            .find(|edge| {
+                relations.contains(&edge.relation.as_str())
+                    && self
+                        .graph
+                        .nodes
+                        .get(&edge.to)
+                        .is_some_and(|node| node.resource_type == resource_type)
+            })
+            .map(|edge| edge.to.as_str())
+    }
+
+    fn subnet_key(&self, key: &'a str) -> Option<&'a str> {
+        match self.graph.nodes.get(key) {
+            Some(node) if node.resource_type == "subnets" => Some(key),
+            _ => self.container(key, &["in-subnet"], "subnets"),
+        }
+    }
+
+    fn vpc_key(&self, key: &'a str) -> Option<&'a str> {
+        match self.graph.nodes.get(key) {
+            Some(node) if node.resource_type == "vpcs" => Some(key),
+            _ => self
+                .container(key, &["in-vpc", "attached-to"], "vpcs")
+                .or_else(|| {
+                    let subnet = self.subnet_key(key)?;
+                    self.container(subnet, &["in-vpc"], "vpcs")
+                }),
+        }
+    }
+
+    fn vpc_of(&self, key: &'a str) -> Option<&'a Resource> {
+        self.graph.nodes.get(self.vpc_key(key)?)
+    }
+
+    fn label(&self, key: &str) -> String {
+        match self.graph.nodes.get(key) {
+            Some(resource) => match &resource.name {
+                Some(name) if *name != resource.id => format!("{} ({})", resource.id, name),
+                _ => resource.id.clone(),
+            },
+            None => key.to_string(),
+        }
+    }
+
+    fn clusters(
+        &self,
+        included: &BTreeSet<&'a str>,
+        index: &HashMap<&str, usize>,
+        clustering: Clustering,
+    ) -> Cluster {
+        let mut root = Cluster::default();
+        // (VPC key, cluster, subnet clusters), in node order
+        let mut vpcs: Vec<(&str, Cluster, SubnetClusters)> = Vec::new();
+
+        for key in included {
+            let node = index[key];
+            let vpc = match clustering {
+                Clustering::None => None,
+                Clustering::Vpc | Clustering::Subnet => self.vpc_key(key),
+            };
+            let Some(vpc) = vpc else {
+                root.nodes.push(node);
+                continue;
+            };
+
+            let position = match vpcs.iter().position(|(k, _, _)| *k == vpc) {
+                Some(position) => position,
+                None => {
+                    let cluster = Cluster {
+                        id: format!("c{}", vpcs.len()),
+                        label: self.label(vpc),
+                        ..Cluster::default()
+                    };
+                    vpcs.push((vpc, cluster, Vec::new()));
+                    vpcs.len() - 1
+                }
+            };
+            let (_, vpc_cluster, subnets) = &mut vpcs[position];
+
+            let subnet = match clustering {
+                Clustering::Subnet => self.subnet_key(key),
+                _ => None,
+            };
+            match subnet {
+                Some(subnet) => {
+                    let position = match subnets.iter().position(|(k, _)| *k == subnet) {
+                        Some(position) => position,
+                        None => {
+                            let cluster = Cluster {
+                                id: format!("{}_{}", vpc_cluster.id, subnets.len()),
+                                label: self.label(subnet),
+                                ..Cluster::default()
+                            };
+                            subnets.push((subnet, cluster));
+                            subnets.len() - 1
+                        }
+                    };
+                    subnets[position].1.nodes.push(node);
+                }
+                None => vpc_cluster.nodes.push(node),
+            }
+        }
+
+        root.children = vpcs
+            .into_iter()
+            .map(|(_, mut cluster, subnets)| {
+                cluster.children = subnets.into_iter().map(|(_, subnet)| subnet).collect();
+                cluster
+            })
+            .collect();
+        root
+    }
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+        .replace('|', "#124;")
+        .replace('\n', "<br/>")
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::normalize::normalize;
+    use crate::models::ResourceCollection;
+    use serde_json::{json, Value};
+
+    fn graph() -> Graph {
+        let collections = [
+            (
+                "vpcs",
+                json!({ "Vpcs": [{ "VpcId": "vpc-1" }, { "VpcId": "vpc-2" }] }),
+            ),
+            (
+                "subnets",
+                json!({ "Subnets": [{ "SubnetId": "subnet-1", "VpcId": "vpc-1" }] }),
+            ),
+            (
+                "instances",
+                json!({ "Reservations": [{ "Instances": [
+                    {
+                        "InstanceId": "i-1", "SubnetId": "subnet-1", "VpcId": "vpc-1",
+                        "Tags": [{ "Key": "Name", "Value": "web \"a\"" }, { "Key": "env", "Value": "prod" }]
+                    },
+                    { "InstanceId": "i-2", "VpcId": "vpc-2" }
+                ]}]}),
+            ),
+        ];
+        let resources = collections
+            .into_iter()
+            .flat_map(|(resource_type, payload): (&str, Value)| {
+                let collection =
+                    ResourceCollection::new("ec2", "us-east-1", resource_type, payload, "now");
+                normalize(&collection, "123456789012", "aws")
+            })
+            .collect();
+        Graph::build(resources)
+    }
+
+    #[test]
+    fn test_export_clusters_by_vpc_and_subnet() {
+        let diagram = Diagram::new(&graph(), &GraphFilter::default(), Clustering::Subnet);
+        let dot = diagram.to_dot();
+        assert!(dot.starts_with("digraph resources {"));
+        assert!(dot.contains("subgraph cluster_c0 {"));
+        assert!(dot.contains("subgraph cluster_c0_0 {"));
+        assert!(dot.contains("label=\"web \\\"a\\\"\\ninstances\""));
+        assert!(dot.contains("[label=\"in-subnet\"]"));
+
+        let mermaid = diagram.to_mermaid();
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("subgraph c0[\"vpc-1\"]"));
+        assert!(mermaid.contains("-->|in-vpc|"));
+
+        let graphml = diagram.to_graphml();
+        assert!(graphml.contains("<graph id=\"c0:\" edgedefault=\"directed\">"));
+        assert!(graphml.contains("web &quot;a&quot;&#10;instances"));
+        assert_eq!(graphml.matches("<edge ").count(), diagram.edges.len());
+    }
+
+    #[test]
+    fn test_filter_by_vpc_and_tag() {
+        let graph = graph();
+        let vpc_2 = GraphFilter {
+            vpcs: vec!["vpc-2".to_string()],
+            ..GraphFilter::default()
+        };
+        let diagram = Diagram::new(&graph, &vpc_2, Clustering::None);
+        let labels: Vec<&str> = diagram
+            .nodes
+            .iter()
+            .map(|node| node.label.as_str())
+            .collect();
+        assert_eq!(labels, vec!["i-2\ninstances", "vpc-2\nvpcs"]);
+
+        let prod = GraphFilter {
+            tags: vec![("env".to_string(), "prod".to_string())],
+            ..GraphFilter::default()
+        };
+        let diagram = Diagram::new(&graph, &prod, Clustering::Vpc);
+        assert_eq!(diagram.nodes.len(), 1);
+        assert!(diagram.edges.is_empty());
+    }
+}
@@ -0,0 +1,1009 @@
+// Native HTTP backend for AWS APIs
+//
+// Translates AWS CLI style invocations (`ecs list-clusters --region ...`) into
+// SigV4-signed HTTP calls for services speaking the AWS JSON protocol, and
+// returns the response in the same shape the CLI prints: parameters are sent
+// with their JSON types and timestamps are rendered as ISO 8601 strings.
+// Commands for other services (query/XML protocols) or using CLI-only features
+// such as `--query` or `--max-items` fall back to the wrapped executor,
+// normally the `aws` subprocess.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+use super::executor::{CliOutput, CliRequest, CommandExecutor};
+use super::regions::{dns_suffix, DEFAULT_REGION};
+use super::sigv4::{self, Credentials, SignableRequest, SigningParams};
+
+/// Maximum number of pages fetched for a single paginated command
+const MAX_PAGES: usize = 1000;
+
+/// Time allowed to connect to an endpoint, as the CLI's `--cli-connect-timeout`
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Time allowed for a single HTTP request, including reading the response
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Pagination token fields, shared by request and response
+const PAGINATION_TOKENS: &[&str] = &["NextToken", "nextToken", "NextMarker"];
+
+/// Parameters that are lists even when a single value is passed
+const LIST_PARAMETERS: &[&str] = &[
+    "clusters",
+    "services",
+    "tasks",
+    "containerInstances",
+    "include",
+    "AttributeNames",
+];
+
+/// Parameters that are integers; other scalar values are sent as strings, so
+/// IDs such as account numbers keep their type
+const INTEGER_PARAMETERS: &[&str] = &["maxResults", "MaxResults", "limit", "Limit"];
+
+/// Pagination flags implemented by the CLI rather than the API
+const CLI_PAGINATION_FLAGS: &[&str] = &["max-items", "page-size", "starting-token", "no-paginate"];
+
+/// Description of a service speaking the AWS JSON protocol
+struct JsonService {
+    /// Service name as used by the AWS CLI
+    cli_name: &'static str,
+    /// Service name used in the SigV4 credential scope
+    signing_name: &'static str,
+    /// Hostname prefix of the regional endpoint
+    endpoint_prefix: &'static str,
+    /// Prefix of the `X-Amz-Target` header
+    target_prefix: &'static str,
+    /// JSON protocol version (`1.0` or `1.1`)
+    json_version: &'static str,
+    /// Whether request members are camelCase instead of PascalCase
+    camel_case: bool,
+    /// Response members that are timestamps, sent as epoch seconds and printed
+    /// by the CLI as ISO 8601; members of the same name are timestamps in every
+    /// operation of a service
+    timestamps: &'static [&'static str],
+}
+
+const JSON_SERVICES: &[JsonService] = &[
+    JsonService {
+        cli_name: "ecs",
+        signing_name: "ecs",
+        endpoint_prefix: "ecs",
+        target_prefix: "AmazonEC2ContainerServiceV20141113",
+        json_version: "1.1",
+        camel_case: true,
+        timestamps: &[
+            "createdAt",
+            "updatedAt",
+            "startedAt",
+            "stoppedAt",
+            "stoppingAt",
+            "pullStartedAt",
+            "pullStoppedAt",
+            "executionStoppedAt",
+            "connectivityAt",
+            "registeredAt",
+            "deregisteredAt",
+            "stabilityStatusAt",
+            "lastStartedAt",
+        ],
+    },
+    JsonService {
+        cli_name: "ecr",
+        signing_name: "ecr",
+        endpoint_prefix: "api.ecr",
+        target_prefix: "AmazonEC2ContainerRegistry_V20150921",
+        json_version: "1.1",
+        camel_case: true,
+        timestamps: &[
+            "createdAt",
+            "updatedAt",
+            "imagePushedAt",
+            "lastRecordedPullTime",
+            "imageScanCompletedAt",
+            "vulnerabilitySourceUpdatedAt",
+        ],
+    },
+    JsonService {
+        cli_name: "logs",
+        signing_name: "logs",
+        endpoint_prefix: "logs",
+        target_prefix: "Logs_20140328",
+        json_version: "1.1",
+        camel_case: true,
+        timestamps: // Logs times are epoch-millisecond numbers, printed unchanged
+        &[],
+    },
+    JsonService {
+        cli_name: "events",
+        signing_name: "events",
+        endpoint_prefix: "events",
+        target_prefix: "AWSEvents",
+        json_version: "1.1",
+        camel_case: false,
+        timestamps: &[
+            "CreationTime",
+            "LastModifiedTime",
+            "LastAuthorizedTime",
+            "EventStartTime",
+            "EventEndTime",
+            "ReplayStartTime",
+            "ReplayEndTime",
+        ],
+    },
+    JsonService {
+        cli_name: "secretsmanager",
+        signing_name: "secretsmanager",
+        endpoint_prefix: "secretsmanager",
+        target_prefix: "secretsmanager",
+        json_version: "1.1",
+        camel_case: false,
+        timestamps: &[
+            "CreatedDate",
+            "LastAccessedDate",
+            "LastChangedDate",
+            "LastRotatedDate",
+            "DeletedDate",
+            "NextRotationDate",
+        ],
+    },
+    JsonService {
+        cli_name: "acm",
+        signing_name: "acm",
+        endpoint_prefix: "acm",
+        target_prefix: "CertificateManager",
+        json_version: "1.1",
+        camel_case: false,
+        timestamps: &[
+            "CreatedAt",
+            "IssuedAt",
+            "ImportedAt",
+            "RevokedAt",
+            "NotBefore",
+            "NotAfter",
+            "UpdatedAt",
+        ],
+    },
+    JsonService {
+        cli_name: "wafv2",
+        signing_name: "wafv2",
+        endpoint_prefix: "wafv2",
+        target_prefix: "AWSWAF_20190729",
+        json_version: "1.1",
+        camel_case: false,
+        timestamps: &[
+            "Timestamp",
+            "PublishTimestamp",
+            "LastUpdateTimestamp",
+            "ExpiryTimestamp",
+        ],
+    },
+    JsonService {
+        cli_name: "route53resolver",
+        signing_name: "route53resolver",
+        endpoint_prefix: "route53resolver",
+        target_prefix: "Route53Resolver",
+        json_version: "1.1",
+        camel_case: false,
+        timestamps: // Route 53 Resolver times are ISO 8601 strings already
+        &[],
+    },
+    JsonService {
+        cli_name: "sqs",
+        signing_name: "sqs",
+        endpoint_prefix: "sqs",
+        target_prefix: "AmazonSQS",
+        json_version: "1.0",
+        camel_case: false,
+        timestamps: // Queue attributes are strings
+        &[],
+    },
+];
+
+/// A CLI invocation parsed into an API call
+struct ApiCall<'a> {
+    service: &'a JsonService,
+    operation: String,
+    params: Map<String, Value>,
+    region: Option<String>,
+    endpoint_url: Option<String>,
+}
+
+impl<'a> ApiCall<'a> {
+    /// Parse CLI arguments, returning `None` when the command is not supported natively
+    fn parse(args: &[String]) -> Option<Self> {
+        let service = JSON_SERVICES
+            .iter()
+            .find(|s| Some(s.cli_name) == args.first().map(|a| a.as_str()))?;
+        let operation = pascal_case(args.get(1)?);
+
+        let mut params = Map::new();
+        let mut region = None;
+        let mut endpoint_url = None;
+
+        let mut i = 2;
+        while i < args.len() {
+            let flag = args[i].strip_prefix("--")?;
+            let mut values = Vec::new();
+            i += 1;
+            while i < args.len() && !args[i].starts_with("--") {
+                values.push(args[i].clone());
+                i += 1;
+            }
+
+            match flag {
+                "region" => region = values.into_iter().next(),
+                "endpoint-url" => endpoint_url = values.into_iter().next(),
+                // JMESPath queries and other CLI-only features need the real CLI
+                "query" | "output" | "cli-input-json" => return None,
+                flag if CLI_PAGINATION_FLAGS.contains(&flag) => return None,
+                _ => {
+                    // Boolean parameters are `--flag` / `--no-flag` without a value
+                    let (flag, enabled) = match flag.strip_prefix("no-") {
+                        Some(flag) if values.is_empty() => (flag, false),
+                        _ => (flag, true),
+                    };
+                    let name = if service.camel_case {
+                        camel_case(flag)
+                    } else {
+                        pascal_case(flag)
+                    };
+                    let value = match values.len() {
+                        0 => Value::Bool(enabled),
+                        1 if !LIST_PARAMETERS.contains(&name.as_str()) => {
+                            parameter_value(&name, values.remove(0))?
+                        }
+                        _ => Value::Array(
+                            values
+                                .into_iter()
+                                .map(|value| parameter_value(&name, value))
+                                .collect::<Option<_>>()?,
+                        ),
+                    };
+                    params.insert(name, value);
+                }
+            }
+        }
+
+        Some(Self {
+            service,
+            operation,
+            params,
+            region,
+            endpoint_url,
+        })
+    }
+}
+
+/// Outcome of a single HTTP request
+enum HttpResponse {
+    Success(Value),
+    Failure(CliOutput),
+}
+
+/// Values resolved per profile through the CLI
+type ProfileCache<T> = Mutex<HashMap<String, Arc<T>>>;
+
+/// Cell of `profile` in `cache`, created empty on first use
+fn profile_cell<T: Default>(cache: &ProfileCache<T>, profile: &str) -> Arc<T> {
+    let mut cells = cache.lock().unwrap();
+    Arc::clone(cells.entry(profile.to_string()).or_default())
+}
+
+/// Executor calling AWS JSON-protocol APIs over HTTP with SigV4
+pub struct HttpExecutor {
+    client: reqwest::Client,
+    fallback: Arc<dyn CommandExecutor>,
+    /// Exported credentials, renewed when they are about to expire
+    credentials: ProfileCache<tokio::sync::Mutex<Option<Credentials>>>,
+    default_region: ProfileCache<OnceCell<String>>,
+}
+
+impl HttpExecutor {
+    pub fn new(fallback: Arc<dyn CommandExecutor>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .connect_timeout(CONNECT_TIMEOUT)
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("HTTP client configuration is valid"),
+            fallback,
+            credentials: Mutex::default(),
+            default_region: Mutex::default(),
+        }
+    }
+
+    /// Resolve a profile's credentials through the CLI credential chain
+    ///
+    /// Exported once and again whenever they are about to expire, as SSO and
+    /// other temporary credentials do during long runs.
+    async fn credentials(&self, profile: &str) -> Result<Credentials> {
+        let cell = profile_cell(&self.credentials, profile);
+        let mut current = cell.lock().await;
+        if let Some(credentials) = current.as_ref().filter(|c| !c.expiring()) {
+            return Ok(credentials.clone());
+        }
+
+        let output = self
+            .fallback
+            .run(&CliRequest {
+                profile: profile.to_string(),
+                role: None,
+                args: ["configure", "export-credentials", "--format", "process"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+            })
+            .await?;
+
+        if !output.success() {
+            anyhow::bail!(
+                "Failed to export credentials for profile '{}': {}",
+                profile,
+                output.stderr.trim()
+            );
+        }
+
+        let credentials: Credentials =
+            serde_json::from_str(&output.stdout).context("Failed to parse exported credentials")?;
+        *current = Some(credentials.clone());
+        Ok(credentials)
+    }
+
+    /// Resolve a profile's default region once per run
+    async fn default_region(&self, profile: &str) -> Result<String> {
+        profile_cell(&self.default_region, profile)
+            .get_or_try_init(|| async {
+                let output = self
+                    .fallback
+                    .run(&CliRequest {
+                        profile: profile.to_string(),
+                        role: None,
+                        args: vec![
+                            "configure".to_string(),
+                            "get".to_string(),
+                            "region".to_string(),
+                        ],
+                    })
+                    .await?;
+
+                let region = output.stdout.trim();
+                Ok::<_, anyhow::Error>(if output.success() && !region.is_empty() {
+                    region.to_string()
+                } else {
+                    DEFAULT_REGION.to_string()
+                })
+            })
+            .await
+            .cloned()
+    }
+
+    /// Send one signed request
+    async fn send(
+        &self,
+        call: &ApiCall<'_>,
+        params: &Map<String, Value>,
+        region: &str,
+        credentials: &Credentials,
+    ) -> Result<HttpResponse> {
+        let url = match &call.endpoint_url {
+            Some(url) => url.clone(),
+            None => format!(
+                "https://{}.{}.{}/",
+                call.service.endpoint_prefix,
+                region,
+                dns_suffix(region)
+            ),
+        };
+        let parsed = reqwest::Url::parse(&url).context(format!("Invalid endpoint URL: {}", url))?;
+        let host = match (parsed.host_str(), parsed.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(anyhow!("Endpoint URL has no host: {}", url)),
+        };
+
+        let body = serde_json::to_vec(params).context("Failed to serialize request body")?;
+        let headers = vec![
+            (
+                "content-type".to_string(),
+                format!("application/x-amz-json-{}", call.service.json_version),
+            ),
+            ("host".to_string(), host),
+            (
+                "x-amz-target".to_string(),
+                format!("{}.{}", call.service.target_prefix, call.operation),
+            ),
+        ];
+
+        let auth_headers = sigv4::sign(
+            &SignableRequest {
+                method: "POST",
+                path: parsed.path(),
+                query: &[],
+                headers: &headers,
+                body: &body,
+            },
+            &SigningParams {
+                credentials,
+                region,
+                service: call.service.signing_name,
+                time: chrono::Utc::now(),
+            },
+        );
+
+        let mut request = self.client.post(parsed.clone()).body(body);
+        for (name, value) in headers.iter().chain(auth_headers.iter()) {
+            if name != "host" {
+                request = request.header(name.as_str(), value.as_str());
+            }
+        }
+
+        // Connection failures and timeouts are worded like the CLI's so they
+        // classify (and retry) identically
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                let reason = match (e.is_timeout(), e.is_connect()) {
+                    (true, true) => "Connect timeout on endpoint URL",
+                    (true, false) => "Read timeout on endpoint URL",
+                    (false, _) => "Could not connect to the endpoint URL",
+                };
+                return Ok(HttpResponse::Failure(CliOutput {
+                    exit_code: 255,
+                    stdout: String::new(),
+                    stderr: format!("{}: \"{}\"", reason, url),
+                }));
+            }
+        };
+
+        let status = response.status();
+        let error_type = response
+            .headers()
+            .get("x-amzn-errortype")
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let text = response
+            .text()
+            .await
+            .context("Failed to read HTTP response body")?;
+        let json: Value = if text.trim().is_empty() {
+            Value::Object(Map::new())
+        } else {
+            serde_json::from_str(&text).unwrap_or(Value::Null)
+        };
+
+        if status.is_success() {
+            return Ok(HttpResponse::Success(json));
+        }
+
+        // Render errors the way the CLI does so they classify identically
+        let code = json
+            .get("__type")
+            .and_then(|t| t.as_str())
+            .map(String::from)
+            .or(error_type)
+            .map(|t| {
+                let t = t.rsplit('#').next().unwrap_or(&t).to_string();
+                t.split(':').next().unwrap_or(&t).to_string()
+            })
+            .unwrap_or_else(|| format!("HTTP{}", status.as_u16()));
+        let message = json
+            .get("message")
+            .or_else(|| json.get("Message"))
+            .and_then(|m| m.as_str())
+            .unwrap_or_default();
+
+        Ok(HttpResponse::Failure(CliOutput {
+            exit_code: 254,
+            stdout: String::new(),
+            stderr: format!(
+                "An error occurred ({}) when calling the {} operation: {}",
+                code, call.operation, message
+            ),
+        }))
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for HttpExecutor {
+    async fn run(&self, request: &CliRequest) -> Result<CliOutput> {
+        let call = match ApiCall::parse(&request.args) {
+            Some(call) => call,
+            None => return self.fallback.run(request).await,
+        };
+
+        let credentials = match &request.role {
+            Some(role) => role.credentials.clone(),
+            None => self.credentials(&request.profile).await?,
+        };
+        let region = match (&call.region, &request.role) {
+            (Some(region), _) => region.clone(),
+            (None, Some(role)) => role.region.clone(),
+            (None, None) => self.default_region(&request.profile).await?,
+        };
+
+        // Follow pagination tokens and merge list fields like the CLI does
+        let mut params = call.params.clone();
+        let mut merged: Option<Map<String, Value>> = None;
+        let mut complete = false;
+
+        for _ in 0..MAX_PAGES {
+            let page = match self.send(&call, &params, &region, &credentials).await? {
+                HttpResponse::Success(Value::Object(page)) => page,
+                HttpResponse::Success(other) => {
+                    return Ok(CliOutput {
+                        exit_code: 0,
+                        stdout: other.to_string(),
+                        stderr: String::new(),
+                    })
+                }
+                HttpResponse::Failure(output) => return Ok(output),
+            };
+
+            let token = PAGINATION_TOKENS.iter().find_map(|name| {
+                page.get(*name)
+                    .and_then(|t| t.as_str())
+                    .map(|t| (name.to_string(), t.to_string()))
+            });
+
+            merged = Some(match merged {
+                None => page,
+                Some(mut acc) => {
+                    for (key, value) in page {
+                        match (acc.get_mut(&key), value) {
+                            (Some(Value::Array(existing)), Value::Array(more)) => {
+                                existing.extend(more)
+                            }
+                            (None, value) => {
+                                acc.insert(key, value);
+                            }
+                            _ => {}
+                        }
+                    }
+                    acc
+                }
+            });
+
+            match token {
+                Some((name, token)) => {
+                    params.insert(name, Value::String(token));
+                }
+                None => {
+                    complete = true;
+                    break;
+                }
+            }
+        }
+
+        // A partial inventory must not pass for a complete one
+        if !complete {
+            return Ok(CliOutput {
+                exit_code: 255,
+                stdout: String::new(),
+                stderr: format!(
+                    "{} returned more than {} pages; the result would be incomplete",
+                    call.operation, MAX_PAGES
+                ),
+            });
+        }
+
+        let mut merged = merged.unwrap_or_default();
+        for name in PAGINATION_TOKENS {
+            merged.remove(*name);
+        }
+        let mut merged = Value::Object(merged);
+        render_timestamps(&mut merged, call.service.timestamps);
+
+        Ok(CliOutput {
+            exit_code: 0,
+            stdout: merged.to_string(),
+            stderr: String::new(),
+        })
+    }
+}
+
+/// Typed value of a parameter given on the command line
+///
+/// JSON values (`--includes '{"keyTypes": [...]}'`) are sent as parsed; returns
+/// `None` for an integer parameter that is not a number.
+fn parameter_value(name: &str, value: String) -> Option<Value> {
+    if INTEGER_PARAMETERS.contains(&name) {
+        return value.parse::<i64>().ok().map(Value::from);
+    }
+    if value.starts_with('{') || value.starts_with('[') {
+        if let Ok(json) = serde_json::from_str(&value) {
+            return Some(json);
+        }
+    }
+    Some(Value::String(value))
+}
+
+/// Replace epoch-second `timestamps` members with the ISO 8601 strings the CLI
+/// prints, e.g. `2024-05-01T12:00:00.250000+00:00`
+fn render_timestamps(value: &mut Value, timestamps: &[&str]) {
+    match value {
+        Value::Object(members) => {
+            for (name, member) in members.iter_mut() {
+                let is_timestamp = timestamps.contains(&name.as_str());
+                match member.as_f64() {
+                    Some(seconds) if is_timestamp => {
+                        if let Some(rendered) = iso8601(seconds) {
+                            *member = Value::String(rendered);
+                        }
+                    }
+                    _ => render_timestamps(member, timestamps),
+                }
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| render_timestamps(item, timestamps)),
+        _ => {}
+    }
+}
+
+/// Python's `datetime.isoformat()` of a UTC time, as rendered by the CLI
+fn iso8601(seconds: f64) -> Option<String> {
+    let micros = (seconds * 1_000_000.0).round() as i64;
+    let time = chrono::DateTime::from_timestamp_micros(micros)?;
+    let format = if micros % 1_000_000 == 0 {
+        "%Y-%m-%dT%H:%M:%S+00:00"
+    } else {
+        "%Y-%m-%dT%H:%M:%S%.6f+00:00"
+    };
+    Some(time.format(format).to_string())
+}
+
+/// `list-event-buses` -> `ListEventBuses`
+fn pascal_case(kebab: &str) -> String {
+    kebab
+        .split('-')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// `container-instances` -> `containerInstances`
+fn camel_case(kebab: &str) -> String {
+    let pascal = pascal_case(kebab);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Serve canned HTTP responses, one per connection, returning the raw requests
+    async fn mock_server(
+        responses: Vec<(u16, Value)>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                loop {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                    let text = String::from_utf8_lossy(&buf).to_string();
+                    if let Some(header_end) = text.find("\r\n\r\n") {
+                        let length = text
+                            .lines()
+                            .find_map(|l| {
+                                l.to_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if buf.len() >= header_end + 4 + length {
+                            break;
+                        }
+                    }
+                }
+                requests.push(String::from_utf8_lossy(&buf).to_string());
+
+                let body = body.to_string();
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: application/x-amz-json-1.1\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+            requests
+        });
+
+        (url, handle)
+    }
+
+    /// CLI configuration of the profiles `default` and `prod`, each with its
+    /// own access key and region
+    struct ProfileConfig;
+
+    #[async_trait]
+    impl CommandExecutor for ProfileConfig {
+        async fn run(&self, request: &CliRequest) -> Result<CliOutput> {
+            let (access_key_id, region) = match request.profile.as_str() {
+                "prod" => ("AKIDPROD", "eu-central-1"),
+                _ => ("AKIDEXAMPLE", "us-east-2"),
+            };
+            let stdout = match request.args[1].as_str() {
+                "export-credentials" => json!({
+                    "Version": 1,
+                    "AccessKeyId": access_key_id,
+                    "SecretAccessKey": "secret",
+                    "SessionToken": "token",
+                })
+                .to_string(),
+                _ => region.to_string(),
+            };
+            Ok(CliOutput {
+                exit_code: 0,
+                stdout,
+                stderr: String::new(),
+            })
+        }
+    }
+
+    fn executor() -> HttpExecutor {
+        HttpExecutor::new(Arc::new(ProfileConfig))
+    }
+
+    /// Temporary credentials valid for `valid_for`, counting exports
+    struct TemporaryCredentials {
+        valid_for: chrono::TimeDelta,
+        exports: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CommandExecutor for TemporaryCredentials {
+        async fn run(&self, _request: &CliRequest) -> Result<CliOutput> {
+            self.exports
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(CliOutput {
+                exit_code: 0,
+                stdout: json!({
+                    "Version": 1,
+                    "AccessKeyId": "ASIAEXAMPLE",
+                    "SecretAccessKey": "secret",
+                    "SessionToken": "token",
+                    "Expiration": (chrono::Utc::now() + self.valid_for).to_rfc3339(),
+                })
+                .to_string(),
+                stderr: String::new(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_parse_maps_flags_to_members() {
+        let call = ApiCall::parse(&args(&[
+            "ecs",
+            "describe-clusters",
+            "--clusters",
+            "arn:cluster",
+            "--include",
+            "TAGS",
+            "--region",
+            "us-west-2",
+        ]))
+        .unwrap();
+
+        assert_eq!(call.operation, "DescribeClusters");
+        assert_eq!(call.region.as_deref(), Some("us-west-2"));
+        assert_eq!(
+            Value::Object(call.params),
+            json!({ "clusters": ["arn:cluster"], "include": ["TAGS"] })
+        );
+
+        assert!(ApiCall::parse(&args(&["ec2", "describe-vpcs"])).is_none());
+        assert!(ApiCall::parse(&args(&["sqs", "list-queues", "--query", "QueueUrls"])).is_none());
+        assert!(ApiCall::parse(&args(&["sqs", "list-queues", "--max-items", "5"])).is_none());
+    }
+
+    #[test]
+    fn test_parse_sends_typed_parameters() {
+        let call = ApiCall::parse(&args(&[
+            "secretsmanager",
+            "list-secrets",
+            "--max-results",
+            "50",
+            "--no-include-planned-deletion",
+            "--filters",
+            r#"[{"Key": "owning-service", "Values": ["rds"]}]"#,
+        ]))
+        .unwrap();
+        assert_eq!(
+            Value::Object(call.params),
+            json!({
+                "MaxResults": 50,
+                "IncludePlannedDeletion": false,
+                "Filters": [{ "Key": "owning-service", "Values": ["rds"] }],
+            })
+        );
+
+        let call = ApiCall::parse(&args(&[
+            "ecr",
+            "describe-images",
+            "--registry-id",
+            "123456789012",
+        ]))
+        .unwrap();
+        assert_eq!(
+            Value::Object(call.params),
+            json!({ "registryId": "123456789012" })
+        );
+    }
+
+    #[test]
+    fn test_timestamps_rendered_like_cli() {
+        let mut response = json!({
+            "services": [{ "createdAt": 1714564800.25, "desiredCount": 2 }],
+            "Name": "api",
+        });
+        render_timestamps(&mut response, &["createdAt"]);
+        assert_eq!(
+            response,
+            json!({
+                "services": [{ "createdAt": "2024-05-01T12:00:00.250000+00:00", "desiredCount": 2 }],
+                "Name": "api",
+            })
+        );
+
+        let acm = JSON_SERVICES.iter().find(|s| s.cli_name == "acm").unwrap();
+        let mut certificate =
+            json!({ "Certificate": { "NotBefore": 1714564800, "NotAfter": 1746100800 } });
+        render_timestamps(&mut certificate, acm.timestamps);
+        assert_eq!(
+            certificate,
+            json!({ "Certificate": {
+                "NotBefore": "2024-05-01T12:00:00+00:00",
+                "NotAfter": "2025-05-01T12:00:00+00:00",
+            }})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_paginated_call_against_mock_server() {
+        let (url, server) = mock_server(vec![
+            (200, json!({ "clusterArns": ["a"], "nextToken": "page-2" })),
+            (200, json!({ "clusterArns": ["b"] })),
+        ])
+        .await;
+
+        let output = executor()
+            .run(&CliRequest {
+                profile: "default".to_string(),
+                role: None,
+                args: args(&[
+                    "ecs",
+                    "list-clusters",
+                    "--region",
+                    "us-west-2",
+                    "--endpoint-url",
+                    &url,
+                ]),
+            })
+            .await
+            .unwrap();
+
+        assert!(output.success());
+        let json: Value = serde_json::from_str(&output.stdout).unwrap();
+        assert_eq!(json, json!({ "clusterArns": ["a", "b"] }));
+
+        let requests = server.await.unwrap();
+        assert!(requests[0]
+            .to_lowercase()
+            .contains("x-amz-target: amazonec2containerservicev20141113.listclusters"));
+        assert!(requests[0].contains("Credential=AKIDEXAMPLE/"));
+        assert!(requests[0].contains("/us-west-2/ecs/aws4_request"));
+        assert!(requests[1].contains("\"nextToken\":\"page-2\""));
+    }
+
+    #[tokio::test]
+    async fn test_error_rendered_like_cli() {
+        let (url, _server) = mock_server(vec![(
+            400,
+            json!({ "__type": "com.amazonaws.secretsmanager#AccessDeniedException", "message": "not allowed" }),
+        )])
+        .await;
+
+        let output = executor()
+            .run(&CliRequest {
+                profile: "default".to_string(),
+                role: None,
+                args: args(&[
+                    "secretsmanager",
+                    "list-secrets",
+                    "--endpoint-url",
+                    &url,
+                    "--region",
+                    "eu-west-1",
+                ]),
+            })
+            .await
+            .unwrap();
+
+        assert!(!output.success());
+        assert_eq!(
+            output.stderr,
+            "An error occurred (AccessDeniedException) when calling the ListSecrets operation: not allowed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_credentials_and_region_resolved_per_profile() {
+        let (url, server) = mock_server(vec![
+            (200, json!({ "clusterArns": [] })),
+            (200, json!({ "clusterArns": [] })),
+        ])
+        .await;
+        let executor = executor();
+
+        for profile in ["default", "prod"] {
+            let output = executor
+                .run(&CliRequest {
+                    profile: profile.to_string(),
+                    role: None,
+                    args: args(&["ecs", "list-clusters", "--endpoint-url", &url]),
+                })
+                .await
+                .unwrap();
+            assert!(output.success());
+        }
+
+        let requests = server.await.unwrap();
+        assert!(requests[0].contains("Credential=AKIDEXAMPLE/"));
+        assert!(requests[0].contains("/us-east-2/ecs/aws4_request"));
+        assert!(requests[1].contains("Credential=AKIDPROD/"));
+        assert!(requests[1].contains("/eu-central-1/ecs/aws4_request"));
+    }
+
+    #[tokio::test]
+    async fn test_expiring_credentials_exported_again() {
+        for (valid_for, exports) in [
+            (chrono::TimeDelta::minutes(1), 2),
+            (chrono::TimeDelta::hours(1), 1),
+        ] {
+            let fallback = Arc::new(TemporaryCredentials {
+                valid_for,
+                exports: Default::default(),
+            });
+            let executor = HttpExecutor::new(fallback.clone());
+
+            executor.credentials("sso").await.unwrap();
+            executor.credentials("sso").await.unwrap();
+
+            assert_eq!(
+                fallback.exports.load(std::sync::atomic::Ordering::SeqCst),
+                exports
+            );
+        }
+    }
+}
@@ -1,6 +1,26 @@
 // AWS module
+pub mod adaptive;
+pub mod arn;
+pub mod audit;
+pub mod cache;
+pub mod cassette;
 pub mod cli;
 pub mod collector_builder;
 pub mod collectors;
+pub mod endpoints;
+pub mod error;
+pub mod executor;
+pub mod graph;
+pub mod graph_export;
+pub mod http;
+pub mod normalize;
+pub mod organizations;
 pub mod parallel_aws;
+pub mod permissions;
+pub mod profiles;
+pub mod rate_limit;
 pub mod regions;
+pub mod retry;
+pub mod sigv4;
+pub mod tags;
+pub mod targets;
@@ -0,0 +1,678 @@
+// Normalized resource model
+//
+// Every collector keeps the raw CLI payload of a resource type, whose shape
+// differs per API (`Reservations[].Instances[]`, `DistributionList.Items[]`,
+// `clusterArns[]`...). The extractors below know those shapes and turn each
+// payload into flat `Resource` records that consumers can use without any
+// service-specific knowledge.
+
+use serde_json::Value;
+
+use super::arn::Arn;
+use super::tags;
+use crate::models::{Resource, ResourceCollection};
+
+/// Where the fields of a resource type live in its payload
+///
+/// Paths are dot-separated keys; a `[]` suffix flattens an array. Each field
+/// lists candidate paths within an item, the first one present wins.
+struct Extractor {
+    items: &'static [&'static str],
+    id: &'static [&'static str],
+    name: &'static [&'static str],
+    arn: &'static [&'static str],
+    created_at: &'static [&'static str],
+}
+
+impl Extractor {
+    const fn new(items: &'static [&'static str], id: &'static [&'static str]) -> Self {
+        Self {
+            items,
+            id,
+            name: &[],
+            arn: &[],
+            created_at: &[],
+        }
+    }
+
+    const fn name(mut self, name: &'static [&'static str]) -> Self {
+        self.name = name;
+        self
+    }
+
+    const fn arn(mut self, arn: &'static [&'static str]) -> Self {
+        self.arn = arn;
+        self
+    }
+
+    const fn created_at(mut self, created_at: &'static [&'static str]) -> Self {
+        self.created_at = created_at;
+        self
+    }
+}
+
+/// Extractor of a resource type, `None` for types that are not resources
+/// (e.g. the IAM password policy)
+fn extractor(service: &str, resource_type: &str) -> Option<Extractor> {
+    let extractor = match (service, resource_type) {
+        // ACM
+        ("acm", "certificates") => Extractor::new(&["Certificates[]"], &["CertificateArn"])
+            .name(&["DomainName"])
+            .arn(&["CertificateArn"])
+            .created_at(&["CreatedAt", "ImportedAt"]),
+
+        // CloudFormation
+        ("cloudformation", "stacks") => Extractor::new(&["Stacks[]"], &["StackName"])
+            .name(&["StackName"])
+            .arn(&["StackId"])
+            .created_at(&["CreationTime"]),
+        ("cloudformation", "stack-sets") => Extractor::new(&["Summaries[]"], &["StackSetId"])
+            .name(&["StackSetName"])
+            .arn(&["StackSetARN"]),
+        ("cloudformation", "exports") => Extractor::new(&["Exports[]"], &["Name"]).name(&["Name"]),
+        ("cloudformation", "change-sets") => Extractor::new(&["ChangeSets[]"], &["ChangeSetId"])
+            .name(&["ChangeSetName"])
+            .arn(&["ChangeSetId"])
+            .created_at(&["CreationTime"]),
+
+        // CloudFront
+        ("cloudfront", "distributions") => Extractor::new(&["Distributions[]"], &["Id"])
+            .name(&["DomainName"])
+            .arn(&["ARN"]),
+        ("cloudfront", "origin-access-identities") => {
+            Extractor::new(&["CloudFrontOriginAccessIdentityList.Items[]"], &["Id"])
+                .name(&["Comment"])
+        }
+        ("cloudfront", "cache-policies") => {
+            Extractor::new(&["CachePolicyList.Items[]"], &["CachePolicy.Id"])
+                .name(&["CachePolicy.CachePolicyConfig.Name"])
+        }
+        ("cloudfront", "origin-request-policies") => Extractor::new(
+            &["OriginRequestPolicyList.Items[]"],
+            &["OriginRequestPolicy.Id"],
+        )
+        .name(&["OriginRequestPolicy.OriginRequestPolicyConfig.Name"]),
+        ("cloudfront", "response-headers-policies") => Extractor::new(
+            &["ResponseHeadersPolicyList.Items[]"],
+            &["ResponseHeadersPolicy.Id"],
+        )
+        .name(&["ResponseHeadersPolicy.ResponseHeadersPolicyConfig.Name"]),
+        ("cloudfront", "functions") => Extractor::new(&["FunctionList.Items[]"], &["Name"])
+            .name(&["Name"])
+            .arn(&["FunctionMetadata.FunctionARN"])
+            .created_at(&["FunctionMetadata.CreatedTime"]),
+
+        // CloudWatch
+        ("cloudwatch", "alarms") => {
+            Extractor::new(&["MetricAlarms[]", "CompositeAlarms[]"], &["AlarmName"])
+                .name(&["AlarmName"])
+                .arn(&["AlarmArn"])
+        }
+        ("cloudwatch", "dashboards") => Extractor::new(&["DashboardEntries[]"], &["DashboardName"])
+            .name(&["DashboardName"])
+            .arn(&["DashboardArn"]),
+        ("cloudwatch", "metric-streams") => Extractor::new(&["Entries[]"], &["Name"])
+            .name(&["Name"])
+            .arn(&["Arn"])
+            .created_at(&["CreationDate"]),
+        ("cloudwatch", "insights-rules") => {
+            Extractor::new(&["InsightRules[]"], &["Name"]).name(&["Name"])
+        }
+        ("cloudwatch", "log-groups") => Extractor::new(&["logGroups[]"], &["logGroupName"])
+            .name(&["logGroupName"])
+            .arn(&["logGroupArn", "arn"])
+            .created_at(&["creationTime"]),
+
+        // EC2 and VPC (the VPC collector shares the network resource types)
+        ("ec2", "instances") => Extractor::new(&["Reservations[].Instances[]"], &["InstanceId"])
+            .created_at(&["LaunchTime"]),
+        ("ec2" | "vpc", "vpcs") => Extractor::new(&["Vpcs[]"], &["VpcId"]),
+        ("ec2" | "vpc", "subnets") => {
+            Extractor::new(&["Subnets[]"], &["SubnetId"]).arn(&["SubnetArn"])
+        }
+        ("ec2" | "vpc", "route-tables") => Extractor::new(&["RouteTables[]"], &["RouteTableId"]),
+        ("ec2" | "vpc", "internet-gateways") => {
+            Extractor::new(&["InternetGateways[]"], &["InternetGatewayId"])
+        }
+        ("ec2" | "vpc", "nat-gateways") => {
+            Extractor::new(&["NatGateways[]"], &["NatGatewayId"]).created_at(&["CreateTime"])
+        }
+        ("ec2" | "vpc", "network-acls") => Extractor::new(&["NetworkAcls[]"], &["NetworkAclId"]),
+        ("ec2" | "vpc", "vpc-endpoints") => Extractor::new(&["VpcEndpoints[]"], &["VpcEndpointId"])
+            .created_at(&["CreationTimestamp"]),
+        ("ec2" | "vpc", "vpc-peering-connections") => {
+            Extractor::new(&["VpcPeeringConnections[]"], &["VpcPeeringConnectionId"])
+        }
+        ("ec2" | "vpc", "customer-gateways") => {
+            Extractor::new(&["CustomerGateways[]"], &["CustomerGatewayId"])
+        }
+        ("vpc", "vpn-gateways") => Extractor::new(&["VpnGateways[]"], &["VpnGatewayId"]),
+        ("ec2", "security-groups") => Extractor::new(&["SecurityGroups[]"], &["GroupId"])
+            .name(&["GroupName"])
+            .arn(&["SecurityGroupArn"]),
+        ("ec2", "elastic-ips") => Extractor::new(&["Addresses[]"], &["AllocationId", "PublicIp"]),
+        ("ec2", "volumes") => {
+            Extractor::new(&["Volumes[]"], &["VolumeId"]).created_at(&["CreateTime"])
+        }
+        ("ec2", "snapshots") => {
+            Extractor::new(&["Snapshots[]"], &["SnapshotId"]).created_at(&["StartTime"])
+        }
+        ("ec2", "images") => Extractor::new(&["Images[]"], &["ImageId"])
+            .name(&["Name"])
+            .created_at(&["CreationDate"]),
+        ("ec2", "key-pairs") => Extractor::new(&["KeyPairs[]"], &["KeyPairId", "KeyName"])
+            .name(&["KeyName"])
+            .created_at(&["CreateTime"]),
+        ("ec2", "network-interfaces") => {
+            Extractor::new(&["NetworkInterfaces[]"], &["NetworkInterfaceId"])
+        }
+        ("ec2", "launch-templates") => {
+            Extractor::new(&["LaunchTemplates[]"], &["LaunchTemplateId"])
+                .name(&["LaunchTemplateName"])
+                .created_at(&["CreateTime"])
+        }
+        ("ec2", "auto-scaling-groups") => {
+            Extractor::new(&["AutoScalingGroups[]"], &["AutoScalingGroupName"])
+                .name(&["AutoScalingGroupName"])
+                .arn(&["AutoScalingGroupARN"])
+                .created_at(&["CreatedTime"])
+        }
+        ("ec2", "placement-groups") => {
+            Extractor::new(&["PlacementGroups[]"], &["GroupId", "GroupName"])
+                .name(&["GroupName"])
+                .arn(&["GroupArn"])
+        }
+        ("ec2", "transit-gateway-attachments") => Extractor::new(
+            &["TransitGatewayAttachments[]"],
+            &["TransitGatewayAttachmentId"],
+        )
+        .created_at(&["CreationTime"]),
+        ("ec2", "vpn-connections") => Extractor::new(&["VpnConnections[]"], &["VpnConnectionId"]),
+
+        // ECR
+        ("ecr", "repositories") => Extractor::new(&["repositories[]"], &["repositoryName"])
+            .name(&["repositoryName"])
+            .arn(&["repositoryArn"])
+            .created_at(&["createdAt"]),
+
+        // ECS
+        ("ecs", "capacity-providers") => Extractor::new(&["capacityProviders[]"], &["name"])
+            .name(&["name"])
+            .arn(&["capacityProviderArn"]),
+        ("ecs", "task-definitions") => Extractor::new(&["taskDefinitionArns[]"], &[]),
+        ("ecs", "clusters") => Extractor::new(&["clusters[]"], &["clusterName"])
+            .name(&["clusterName"])
+            .arn(&["clusterArn"]),
+        ("ecs", "services") => Extractor::new(&["services[]"], &["serviceName"])
+            .name(&["serviceName"])
+            .arn(&["serviceArn"])
+            .created_at(&["createdAt"]),
+        ("ecs", "tasks") => Extractor::new(&["tasks[]"], &["taskArn"])
+            .arn(&["taskArn"])
+            .created_at(&["createdAt"]),
+        ("ecs", "container-instances") => {
+            Extractor::new(&["containerInstances[]"], &["containerInstanceArn"])
+                .name(&["ec2InstanceId"])
+                .arn(&["containerInstanceArn"])
+                .created_at(&["registeredAt"])
+        }
+
+        // ElastiCache
+        ("elasticache", "cache-clusters") => {
+            Extractor::new(&["CacheClusters[]"], &["CacheClusterId"])
+                .arn(&["ARN"])
+                .created_at(&["CacheClusterCreateTime"])
+        }
+        ("elasticache", "replication-groups") => {
+            Extractor::new(&["ReplicationGroups[]"], &["ReplicationGroupId"])
+                .name(&["Description"])
+                .arn(&["ARN"])
+                .created_at(&["ReplicationGroupCreateTime"])
+        }
+        ("elasticache", "cache-subnet-groups") => {
+            Extractor::new(&["CacheSubnetGroups[]"], &["CacheSubnetGroupName"]).arn(&["ARN"])
+        }
+        ("elasticache", "cache-parameter-groups") => {
+            Extractor::new(&["CacheParameterGroups[]"], &["CacheParameterGroupName"]).arn(&["ARN"])
+        }
+        ("elasticache", "cache-security-groups") => {
+            Extractor::new(&["CacheSecurityGroups[]"], &["CacheSecurityGroupName"]).arn(&["ARN"])
+        }
+        ("elasticache", "snapshots") => {
+            Extractor::new(&["Snapshots[]"], &["SnapshotName"]).arn(&["ARN"])
+        }
+        ("elasticache", "user-groups") => {
+            Extractor::new(&["UserGroups[]"], &["UserGroupId"]).arn(&["ARN"])
+        }
+
+        // Elastic Load Balancing
+        ("elb", "classic-load-balancers") => {
+            Extractor::new(&["LoadBalancerDescriptions[]"], &["LoadBalancerName"])
+                .name(&["LoadBalancerName"])
+                .created_at(&["CreatedTime"])
+        }
+        ("elb", "load-balancers") => Extractor::new(&["LoadBalancers[]"], &["LoadBalancerName"])
+            .name(&["LoadBalancerName"])
+            .arn(&["LoadBalancerArn"])
+            .created_at(&["CreatedTime"]),
+        ("elb", "target-groups") => Extractor::new(&["TargetGroups[]"], &["TargetGroupName"])
+            .name(&["TargetGroupName"])
+            .arn(&["TargetGroupArn"]),
+        ("elb", "listeners") => {
+            Extractor::new(&["Listeners[]"], &["ListenerArn"]).arn(&["ListenerArn"])
+        }
+
+        // EventBridge
+        ("eventbridge", "event-buses") => Extractor::new(&["EventBuses[]"], &["Name"])
+            .name(&["Name"])
+            .arn(&["Arn"])
+            .created_at(&["CreationTime"]),
+        ("eventbridge", "archives") => Extractor::new(&["Archives[]"], &["ArchiveName"])
+            .name(&["ArchiveName"])
+            .created_at(&["CreationTime"]),
+        ("eventbridge", "api-destinations") => Extractor::new(&["ApiDestinations[]"], &["Name"])
+            .name(&["Name"])
+            .arn(&["ApiDestinationArn"])
+            .created_at(&["CreationTime"]),
+        ("eventbridge", "connections") => Extractor::new(&["Connections[]"], &["Name"])
+            .name(&["Name"])
+            .arn(&["ConnectionArn"])
+            .created_at(&["CreationTime"]),
+        ("eventbridge", "replays") => Extractor::new(&["Replays[]"], &["ReplayName"])
+            .name(&["ReplayName"])
+            .created_at(&["ReplayStartTime"]),
+
+        // IAM
+        ("iam", "users") => Extractor::new(&["Users[]"], &["UserId"])
+            .name(&["UserName"])
+            .arn(&["Arn"])
+            .created_at(&["CreateDate"]),
+        ("iam", "roles") => Extractor::new(&["Roles[]"], &["RoleId"])
+            .name(&["RoleName"])
+            .arn(&["Arn"])
+            .created_at(&["CreateDate"]),
+        ("iam", "groups") => Extractor::new(&["Groups[]"], &["GroupId"])
+            .name(&["GroupName"])
+            .arn(&["Arn"])
+            .created_at(&["CreateDate"]),
+        ("iam", "policies") => Extractor::new(&["Policies[]"], &["PolicyId"])
+            .name(&["PolicyName"])
+            .arn(&["Arn"])
+            .created_at(&["CreateDate"]),
+        ("iam", "saml-providers") => Extractor::new(&["SAMLProviderList[]"], &["Arn"])
+            .arn(&["Arn"])
+            .created_at(&["CreateDate"]),
+        ("iam", "oidc-providers") => {
+            Extractor::new(&["OpenIDConnectProviderList[]"], &["Arn"]).arn(&["Arn"])
+        }
+        ("iam", "instance-profiles") => {
+            Extractor::new(&["InstanceProfiles[]"], &["InstanceProfileId"])
+                .name(&["InstanceProfileName"])
+                .arn(&["Arn"])
+                .created_at(&["CreateDate"])
+        }
+
+        // Lambda
+        ("lambda", "functions") => Extractor::new(&["Functions[]"], &["FunctionName"])
+            .name(&["FunctionName"])
+            .arn(&["FunctionArn"]),
+        ("lambda", "layers") => Extractor::new(&["Layers[]"], &["LayerName"])
+            .name(&["LayerName"])
+            .arn(&["LayerArn"])
+            .created_at(&["LatestMatchingVersion.CreatedDate"]),
+        ("lambda", "code-signing-configs") => {
+            Extractor::new(&["CodeSigningConfigs[]"], &["CodeSigningConfigId"])
+                .name(&["Description"])
+                .arn(&["CodeSigningConfigArn"])
+        }
+
+        // RDS
+        ("rds", "db-instances") => Extractor::new(&["DBInstances[]"], &["DBInstanceIdentifier"])
+            .name(&["DBInstanceIdentifier"])
+            .arn(&["DBInstanceArn"])
+            .created_at(&["InstanceCreateTime"]),
+        ("rds", "db-clusters") => Extractor::new(&["DBClusters[]"], &["DBClusterIdentifier"])
+            .name(&["DBClusterIdentifier"])
+            .arn(&["DBClusterArn"])
+            .created_at(&["ClusterCreateTime"]),
+        ("rds", "db-snapshots") => Extractor::new(&["DBSnapshots[]"], &["DBSnapshotIdentifier"])
+            .arn(&["DBSnapshotArn"])
+            .created_at(&["SnapshotCreateTime"]),
+        ("rds", "db-cluster-snapshots") => {
+            Extractor::new(&["DBClusterSnapshots[]"], &["DBClusterSnapshotIdentifier"])
+                .arn(&["DBClusterSnapshotArn"])
+                .created_at(&["SnapshotCreateTime"])
+        }
+        ("rds", "db-subnet-groups") => {
+            Extractor::new(&["DBSubnetGroups[]"], &["DBSubnetGroupName"])
+                .name(&["DBSubnetGroupName"])
+                .arn(&["DBSubnetGroupArn"])
+        }
+        ("rds", "db-parameter-groups") => {
+            Extractor::new(&["DBParameterGroups[]"], &["DBParameterGroupName"])
+                .name(&["DBParameterGroupName"])
+                .arn(&["DBParameterGroupArn"])
+        }
+        ("rds", "db-cluster-parameter-groups") => Extractor::new(
+            &["DBClusterParameterGroups[]"],
+            &["DBClusterParameterGroupName"],
+        )
+        .name(&["DBClusterParameterGroupName"])
+        .arn(&["DBClusterParameterGroupArn"]),
+        ("rds", "option-groups") => Extractor::new(&["OptionGroupsList[]"], &["OptionGroupName"])
+            .name(&["OptionGroupName"])
+            .arn(&["OptionGroupArn"]),
+        ("rds", "db-security-groups") => {
+            Extractor::new(&["DBSecurityGroups[]"], &["DBSecurityGroupName"])
+                .name(&["DBSecurityGroupName"])
+                .arn(&["DBSecurityGroupArn"])
+        }
+        ("rds", "db-proxies") => Extractor::new(&["DBProxies[]"], &["DBProxyName"])
+            .name(&["DBProxyName"])
+            .arn(&["DBProxyArn"])
+            .created_at(&["CreatedDate"]),
+        ("rds", "event-subscriptions") => {
+            Extractor::new(&["EventSubscriptionsList[]"], &["CustSubscriptionId"])
+                .name(&["CustSubscriptionId"])
+                .arn(&["EventSubscriptionArn"])
+                .created_at(&["SubscriptionCreationTime"])
+        }
+        ("rds", "reserved-db-instances") => {
+            Extractor::new(&["ReservedDBInstances[]"], &["ReservedDBInstanceId"])
+                .arn(&["ReservedDBInstanceArn"])
+                .created_at(&["StartTime"])
+        }
+
+        // Route 53
+        ("route53", "hosted-zones") => Extractor::new(&["HostedZones[]"], &["Id"]).name(&["Name"]),
+        ("route53", "health-checks") => Extractor::new(&["HealthChecks[]"], &["Id"]),
+        ("route53", "traffic-policies") => {
+            Extractor::new(&["TrafficPolicySummaries[]"], &["Id"]).name(&["Name"])
+        }
+        ("route53", "resolver-rules") => Extractor::new(&["ResolverRules[]"], &["Id"])
+            .name(&["Name"])
+            .arn(&["Arn"])
+            .created_at(&["CreationTime"]),
+        ("route53", "resolver-endpoints") => Extractor::new(&["ResolverEndpoints[]"], &["Id"])
+            .name(&["Name"])
+            .arn(&["Arn"])
+            .created_at(&["CreationTime"]),
+
+        // S3
+        ("s3", "buckets") => Extractor::new(&["Buckets[]"], &["Name"])
+            .name(&["Name"])
+            .created_at(&["CreationDate"]),
+
+        // Secrets Manager
+        ("secretsmanager", "secrets") => Extractor::new(&["SecretList[]"], &["Name"])
+            .name(&["Name"])
+            .arn(&["ARN"])
+            .created_at(&["CreatedDate"]),
+
+        // SES
+        ("ses", "identities") => Extractor::new(&["Identities[]"], &[]),
+        ("ses", "configuration-sets") => {
+            Extractor::new(&["ConfigurationSets[]"], &["Name"]).name(&["Name"])
+        }
+        ("ses", "receipt-rule-sets") => Extractor::new(&["RuleSets[]"], &["Name"])
+            .name(&["Name"])
+            .created_at(&["CreatedTimestamp"]),
+        ("ses", "templates") => Extractor::new(&["TemplatesMetadata[]"], &["Name"])
+            .name(&["Name"])
+            .created_at(&["CreatedTimestamp"]),
+        ("ses", "custom-verification-email-templates") => {
+            Extractor::new(&["CustomVerificationEmailTemplates[]"], &["TemplateName"])
+                .name(&["TemplateName"])
+        }
+
+        // SNS
+        ("sns", "topics") => Extractor::new(&["Topics[]"], &["TopicArn"])
+            .name(&["Attributes.Attributes.DisplayName"])
+            .arn(&["TopicArn"]),
+        ("sns", "platform-applications") => {
+            Extractor::new(&["PlatformApplications[]"], &["PlatformApplicationArn"])
+                .arn(&["PlatformApplicationArn"])
+        }
+
+        // SQS
+        ("sqs", "queues") => {
+            Extractor::new(&["Queues[]"], &["QueueUrl"]).arn(&["Attributes.Attributes.QueueArn"])
+        }
+
+        // WAF
+        (
+            "waf",
+            "web-acls-regional"
+            | "web-acls-cloudfront"
+            | "ip-sets"
+            | "regex-pattern-sets"
+            | "rule-groups",
+        ) => Extractor::new(
+            &[
+                "WebACLs[]",
+                "IPSets[]",
+                "RegexPatternSets[]",
+                "RuleGroups[]",
+            ],
+            &["Id"],
+        )
+        .name(&["Name"])
+        .arn(&["ARN"]),
+
+        _ => return None,
+    };
+
+    Some(extractor)
+}
+
+/// Normalize the resources of a collection
+///
+/// Resource types without an extractor, and failed collections, yield nothing.
+/// `partition` is the account's, used for ARNs the payload does not report.
+pub fn normalize(collection: &ResourceCollection, account: &str, partition: &str) -> Vec<Resource> {
+    let Some(extractor) = extractor(&collection.service, &collection.resource_type) else {
+        return Vec::new();
+    };
+
+    extractor
+        .items
+        .iter()
+        .flat_map(|path| select(&collection.resources, path))
+        .filter_map(|item| {
+            // Some list APIs return bare identifiers (`taskDefinitionArns`)
+            let (id, arn) = match item.as_str() {
+                Some(id) => match id.parse::<Arn>() {
+                    Ok(arn) => (arn.resource_id().to_string(), Some(arn)),
+                    Err(_) => (id.to_string(), None),
+                },
+                None => (
+                    first_string(item, extractor.id)?,
+                    first_string(item, extractor.arn).and_then(|arn| arn.parse().ok()),
+                ),
+            };
+            // Build the ARN when the payload does not report one
+            let arn = arn.or_else(|| {
+                Arn::for_resource(
+                    partition,
+                    &collection.service,
+                    &collection.resource_type,
+                    &collection.region,
+                    account,
+                    &id,
+                )
+            });
+            let tags = tags::extract_tags(item);
+            let name = first_string(item, extractor.name).or_else(|| tags.get("Name").cloned());
+
+            Some(Resource {
+                arn: arn.map(|arn| arn.to_string()),
+                id,
+                name,
+                service: collection.service.clone(),
+                resource_type: collection.resource_type.clone(),
+                region: collection.region.clone(),
+                account: account.to_string(),
+                tags,
+                created_at: first_timestamp(item, extractor.created_at),
+                raw: item.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Values at a path such as `Reservations[].Instances[]`
+pub fn select<'a>(value: &'a Value, path: &str) -> Vec<&'a Value> {
+    let mut values = vec![value];
+    for segment in path.split('.') {
+        let (key, flatten) = match segment.strip_suffix("[]") {
+            Some(key) => (key, true),
+            None => (segment, false),
+        };
+        values = values
+            .into_iter()
+            .filter_map(|value| value.get(key))
+            .flat_map(|value| match (flatten, value.as_array()) {
+                (true, Some(items)) => items.iter().collect(),
+                (true, None) => Vec::new(),
+                (false, _) => vec![value],
+            })
+            .collect();
+    }
+    values
+}
+
+/// First candidate path of an item holding a string
+fn first_string(item: &Value, paths: &[&str]) -> Option<String> {
+    paths
+        .iter()
+        .find_map(|path| select(item, path).first()?.as_str().map(String::from))
+}
+
+/// First candidate timestamp of an item
+///
+/// Most APIs report RFC 3339 strings; CloudWatch Logs reports epoch
+/// milliseconds, which are converted.
+fn first_timestamp(item: &Value, paths: &[&str]) -> Option<String> {
+    paths.iter().find_map(|path| {
+        let value = *select(item, path).first()?;
+        match value {
+            Value::String(timestamp) => Some(timestamp.clone()),
+            Value::Number(millis) => chrono::DateTime::from_timestamp_millis(millis.as_i64()?)
+                .map(|timestamp| timestamp.to_rfc3339()),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn collection(service: &str, resource_type: &str, resources: Value) -> ResourceCollection {
+        ResourceCollection::new(
+            service,
+            "us-east-1",
+            resource_type,
+            resources,
+            "2026-01-18T04:12:01+00:00",
+        )
+    }
+
+    #[test]
+    fn test_normalize_flattens_nested_items_and_reads_tags() {
+        let instances = collection(
+            "ec2",
+            "instances",
+            json!({ "Reservations": [
+                { "Instances": [{
+                    "InstanceId": "i-0abc",
+                    "LaunchTime": "2025-06-01T10:00:00+00:00",
+                    "Tags": [{ "Key": "Name", "Value": "web" }, { "Key": "env", "Value": "prod" }]
+                }]},
+                { "Instances": [{ "InstanceId": "i-0def" }] }
+            ]}),
+        );
+
+        let resources = normalize(&instances, "123456789012", "aws");
+        assert_eq!(resources.len(), 2);
+        assert_eq!(resources[0].id, "i-0abc");
+        assert_eq!(resources[0].name.as_deref(), Some("web"));
+        assert_eq!(resources[0].tags["env"], "prod");
+        assert_eq!(
+            resources[0].created_at.as_deref(),
+            Some("2025-06-01T10:00:00+00:00")
+        );
+        assert_eq!(resources[0].account, "123456789012");
+        assert_eq!(
+            resources[0].arn.as_deref(),
+            Some("arn:aws:ec2:us-east-1:123456789012:instance/i-0abc")
+        );
+        assert_eq!(resources[1].raw["InstanceId"], "i-0def");
+    }
+
+    #[test]
+    fn test_normalize_reads_arns_bare_identifiers_and_epoch_timestamps() {
+        let task_definitions = collection(
+            "ecs",
+            "task-definitions",
+            json!({ "taskDefinitionArns": [
+                "arn:aws:ecs:us-east-1:123456789012:task-definition/web:3"
+            ]}),
+        );
+        let resources = normalize(&task_definitions, "123456789012", "aws");
+        assert_eq!(
+            resources[0].arn.as_deref(),
+            Some("arn:aws:ecs:us-east-1:123456789012:task-definition/web:3")
+        );
+        assert_eq!(resources[0].id, "web:3");
+
+        let log_groups = collection(
+            "cloudwatch",
+            "log-groups",
+            json!({ "logGroups": [{
+                "logGroupName": "/aws/lambda/f",
+                "arn": "arn:aws:logs:us-east-1:123456789012:log-group:/aws/lambda/f:*",
+                "creationTime": 1735689600000u64
+            }]}),
+        );
+        let resources = normalize(&log_groups, "123456789012", "aws");
+        assert_eq!(resources[0].id, "/aws/lambda/f");
+        assert_eq!(
+            resources[0].created_at.as_deref(),
+            Some("2025-01-01T00:00:00+00:00")
+        );
+
+        // Failed collections and non-resource types yield nothing
+        assert!(normalize(&collection("s3", "buckets", Value::Null), "1", "aws").is_empty());
+        assert!(normalize(
+            &collection("iam", "password-policy", json!({ "PasswordPolicy": {} })),
+            "1",
+            "aws"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_normalize_builds_global_arns_in_account_partition() {
+        let identities = ResourceCollection::new(
+            "cloudfront",
+            "global",
+            "origin-access-identities",
+            json!({ "CloudFrontOriginAccessIdentityList": { "Items": [{ "Id": "E1OAI" }] } }),
+            "2025-01-01T00:00:00Z",
+        );
+
+        let resources = normalize(&identities, "123456789012", "aws-cn");
+        assert_eq!(
+            resources[0].arn.as_deref(),
+            Some("arn:aws-cn:cloudfront::123456789012:origin-access-identity/E1OAI")
+        );
+
+        let resources = normalize(&identities, "123456789012", "aws");
+        assert!(resources[0]
+            .arn
+            .as_deref()
+            .is_some_and(|arn| arn.starts_with("arn:aws:")));
+    }
+}
@@ -0,0 +1,269 @@
+// AWS Organizations account discovery
+//
+// In `--organization` mode the management profile lists the member accounts,
+// optionally narrowed to organizational units or account tags. The management
+// account is collected with the profile's own credentials and every other
+// active account through a role assumed in it.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::HashSet;
+
+use super::cli::AwsCli;
+use super::targets::{self, CollectionTarget};
+use crate::models::CollectionFailure;
+
+/// Role created by AWS Organizations in every account it creates
+pub const DEFAULT_ROLE_NAME: &str = "OrganizationAccountAccessRole";
+
+/// Accounts of the organization to collect
+#[derive(Debug, Clone, Default)]
+pub struct OrganizationFilter {
+    /// Organizational units whose accounts (including nested OUs) are collected
+    pub ous: Vec<String>,
+    /// Tags an account must carry, as `(key, value)`
+    pub tags: Vec<(String, String)>,
+}
+
+/// IDs of the active accounts matching the filter
+pub async fn list_accounts(cli: &AwsCli, filter: &OrganizationFilter) -> Result<Vec<String>> {
+    let accounts = if filter.ous.is_empty() {
+        let response = cli
+            .execute(&["organizations", "list-accounts"])
+            .await
+            .context("Failed to list organization accounts")?;
+        active_accounts(&response)
+    } else {
+        accounts_under(cli, &filter.ous).await?
+    };
+
+    if filter.tags.is_empty() {
+        return Ok(accounts);
+    }
+
+    let mut tagged = Vec::new();
+    for account_id in accounts {
+        let response = cli
+            .execute(&[
+                "organizations",
+                "list-tags-for-resource",
+                "--resource-id",
+                &account_id,
+            ])
+            .await
+            .context(format!("Failed to list tags of account {}", account_id))?;
+        if has_tags(&response, &filter.tags) {
+            tagged.push(account_id);
+        }
+    }
+
+    Ok(tagged)
+}
+
+/// Resolve the targets of an organization run
+///
+/// Accounts whose role cannot be assumed are reported in the returned
+/// failures and skipped.
+pub async fn resolve_targets(
+    cli: &AwsCli,
+    filter: &OrganizationFilter,
+    role_name: &str,
+    default_region: &str,
+) -> Result<(Vec<CollectionTarget>, Vec<CollectionFailure>)> {
+    let accounts = list_accounts(cli, filter).await?;
+    if accounts.is_empty() {
+        anyhow::bail!("No active organization accounts match the filter");
+    }
+    println!("🏢 Found {} active organization accounts", accounts.len());
+
+    let management = targets::caller_target(cli).await?;
+    let role_arns: Vec<String> = accounts
+        .iter()
+        .filter(|account_id| **account_id != management.account_id)
+        .map(|account_id| targets::role_arn_for(&management.partition, account_id, role_name))
+        .collect();
+
+    let (mut member_targets, failures) =
+        targets::assume_targets(cli, &role_arns, default_region).await?;
+    if accounts.contains(&management.account_id) {
+        member_targets.insert(0, management);
+    }
+
+    Ok((member_targets, failures))
+}
+
+/// Active accounts of the given OUs and all OUs nested below them
+async fn accounts_under(cli: &AwsCli, ous: &[String]) -> Result<Vec<String>> {
+    let mut accounts = Vec::new();
+    let mut seen = HashSet::new();
+    let mut pending: Vec<String> = ous.to_vec();
+
+    while let Some(parent_id) = pending.pop() {
+        let response = cli
+            .execute(&[
+                "organizations",
+                "list-accounts-for-parent",
+                "--parent-id",
+                &parent_id,
+            ])
+            .await
+            .context(format!("Failed to list accounts of {}", parent_id))?;
+        for account_id in active_accounts(&response) {
+            if seen.insert(account_id.clone()) {
+                accounts.push(account_id);
+            }
+        }
+
+        let response = cli
+            .execute(&[
+                "organizations",
+                "list-organizational-units-for-parent",
+                "--parent-id",
+                &parent_id,
+            ])
+            .await
+            .context(format!(
+                "Failed to list organizational units of {}",
+                parent_id
+            ))?;
+        if let Some(units) = response["OrganizationalUnits"].as_array() {
+            pending.extend(
+                units
+                    .iter()
+                    .filter_map(|unit| unit["Id"].as_str())
+                    .map(String::from),
+            );
+        }
+    }
+
+    Ok(accounts)
+}
+
+/// IDs of the active accounts in a `list-accounts*` response
+///
+/// Newer responses report `State`, older ones only the deprecated `Status`.
+fn active_accounts(response: &Value) -> Vec<String> {
+    response["Accounts"]
+        .as_array()
+        .map(|accounts| {
+            accounts
+                .iter()
+                .filter(|account| {
+                    account["State"]
+                        .as_str()
+                        .or_else(|| account["Status"].as_str())
+                        == Some("ACTIVE")
+                })
+                .filter_map(|account| account["Id"].as_str())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether a `list-tags-for-resource` response carries every wanted tag
+fn has_tags(response: &Value, wanted: &[(String, String)]) -> bool {
+    let tags = response["Tags"].as_array().cloned().unwrap_or_default();
+    wanted.iter().all(|(key, value)| {
+        tags.iter().any(|tag| {
+            tag["Key"].as_str() == Some(key.as_str()) && tag["Value"].as_str() == Some(value)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::executor::FakeExecutor;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_list_accounts_walks_nested_ous_and_filters_tags() {
+        let fake = Arc::new(
+            FakeExecutor::new()
+                .respond(
+                    &[
+                        "organizations",
+                        "list-accounts-for-parent",
+                        "--parent-id",
+                        "ou-root-prod",
+                    ],
+                    json!({ "Accounts": [
+                        { "Id": "111111111111", "Status": "ACTIVE" },
+                        { "Id": "222222222222", "Status": "SUSPENDED" },
+                    ]}),
+                )
+                .respond(
+                    &[
+                        "organizations",
+                        "list-organizational-units-for-parent",
+                        "--parent-id",
+                        "ou-root-prod",
+                    ],
+                    json!({ "OrganizationalUnits": [{ "Id": "ou-prod-eu" }] }),
+                )
+                .respond(
+                    &[
+                        "organizations",
+                        "list-accounts-for-parent",
+                        "--parent-id",
+                        "ou-prod-eu",
+                    ],
+                    json!({ "Accounts": [
+                        { "Id": "333333333333", "State": "ACTIVE" },
+                        { "Id": "444444444444", "State": "ACTIVE" },
+                    ]}),
+                )
+                .respond(
+                    &[
+                        "organizations",
+                        "list-organizational-units-for-parent",
+                        "--parent-id",
+                        "ou-prod-eu",
+                    ],
+                    json!({ "OrganizationalUnits": [] }),
+                )
+                .respond(
+                    &[
+                        "organizations",
+                        "list-tags-for-resource",
+                        "--resource-id",
+                        "111111111111",
+                    ],
+                    json!({ "Tags": [{ "Key": "env", "Value": "prod" }] }),
+                )
+                .respond(
+                    &[
+                        "organizations",
+                        "list-tags-for-resource",
+                        "--resource-id",
+                        "333333333333",
+                    ],
+                    json!({ "Tags": [{ "Key": "env", "Value": "staging" }] }),
+                )
+                .respond(
+                    &[
+                        "organizations",
+                        "list-tags-for-resource",
+                        "--resource-id",
+                        "444444444444",
+                    ],
+                    json!({ "Tags": [
+                        { "Key": "team", "Value": "data" },
+                        { "Key": "env", "Value": "prod" },
+                    ]}),
+                ),
+        );
+        let cli = AwsCli::with_executor("management".to_string(), fake);
+
+        let filter = OrganizationFilter {
+            ous: vec!["ou-root-prod".to_string()],
+            tags: vec![("env".to_string(), "prod".to_string())],
+        };
+        let mut accounts = list_accounts(&cli, &filter).await.unwrap();
+        accounts.sort();
+
+        assert_eq!(accounts, vec!["111111111111", "444444444444"]);
+    }
+}
@@ -1,10 +1,13 @@
 // AWS-specific parallel execution adapters
 //
 // This module provides AWS CLI-specific implementations using the generic
-// parallel utilities from utils::parallel
+// parallel utilities from utils::parallel. With adaptive concurrency, each
+// fan-out runs at most the called service's current AIMD limit at once (see
+// `AwsCli::concurrency_for`), so throttling also shrinks new fan-outs.
 
 use crate::aws::cli::AwsCli;
-use crate::models::ResourceCollection;
+use crate::aws::error::AwsErrorKind;
+use crate::models::{CollectionError, ResourceCollection};
 use crate::utils::parallel;
 use futures::stream::{self, StreamExt};
 use serde_json::Value;
@@ -15,6 +18,9 @@ const AWS_COLLECTION_CONCURRENCY: usize = 10;
 /// Default concurrency for AWS detail fetching
 const AWS_DETAIL_CONCURRENCY: usize = 10;
 
+/// A resource augmented with its details, plus the detail commands that failed
+pub type DetailedResource = (Value, Vec<CollectionError>);
+
 // ============================================================================
 // Configuration Types
 // ============================================================================
@@ -54,41 +60,61 @@ impl DetailConfig {
 ///
 /// # Returns
 /// Vector of detailed resources (order not preserved)
-pub async fn fetch_details_parallel<T, F, Fut>(
+pub async fn fetch_details_parallel<T, R, F, Fut>(
     resources: Vec<T>,
     concurrency: usize,
     fetch_fn: F,
-) -> Vec<Value>
+) -> Vec<R>
 where
     T: Send,
+    R: Send,
     F: Fn(T) -> Fut,
-    Fut: std::future::Future<Output = Value> + Send,
+    Fut: std::future::Future<Output = R> + Send,
 {
     parallel::execute_parallel(resources, concurrency, fetch_fn).await
 }
 
+/// Separate detailed resources from the errors recorded while fetching them
+pub fn split_details(results: Vec<DetailedResource>) -> (Vec<Value>, Vec<CollectionError>) {
+    let mut resources = Vec::with_capacity(results.len());
+    let mut errors = Vec::new();
+    for (resource, resource_errors) in results {
+        resources.push(resource);
+        errors.extend(resource_errors);
+    }
+    (resources, errors)
+}
+
 /// Fetch multiple detail fields for a single AWS resource in parallel
 ///
+/// A detail whose configuration does not exist (e.g. a bucket without a CORS
+/// configuration) is recorded as `null`; a detail that could not be fetched
+/// (e.g. missing permission) is left out and its failure is returned.
+///
 /// # Arguments
 /// * `cli` - AWS CLI instance
-/// * `region` - AWS region
+/// * `region` - AWS region, empty to use the CLI's default endpoint
 /// * `base_resource` - Base resource object to augment with details
 /// * `detail_configs` - Vector of detail configurations to fetch
 ///
 /// # Returns
-/// Resource object with all detail fields added
+/// Resource object with all detail fields added, and the failed detail commands
 pub async fn fetch_resource_details(
     cli: &AwsCli,
     region: &str,
     base_resource: Value,
     detail_configs: Vec<DetailConfig>,
-) -> Value {
+) -> DetailedResource {
     let mut resource = base_resource;
     let obj = resource
         .as_object_mut()
         .expect("Resource must be a JSON object");
 
     // Execute all detail fetches in parallel using generic utilities
+    let concurrency = match detail_configs.first() {
+        Some(config) => cli.concurrency_for(&config.command[0], AWS_DETAIL_CONCURRENCY),
+        None => AWS_DETAIL_CONCURRENCY,
+    };
     let cli_clone = cli.clone();
     let region_clone = region.to_string();
 
@@ -101,8 +127,8 @@ pub async fn fetch_resource_details(
             async move {
                 let mut command = config.command.clone();
 
-                // Add region if not already present
-                if !command.contains(&"--region".to_string()) {
+                // Add region if not already present (global services have none)
+                if !region.is_empty() && !command.contains(&"--region".to_string()) {
                     command.push("--region".to_string());
                     command.push(region);
                 }
@@ -111,11 +137,15 @@ pub async fn fetch_resource_details(
                 let result = cli.execute(&args).await;
 
                 match result {
-                    Ok(value) => Some((config.field_name, value)),
-                    Err(_) if config.ignore_errors => None,
+                    Ok(value) => Ok((config.field_name, value)),
+                    Err(e) if AwsErrorKind::of(&e) == AwsErrorKind::NotFound => {
+                        Ok((config.field_name, Value::Null))
+                    }
                     Err(e) => {
-                        eprintln!("Error fetching {}: {}", config.field_name, e);
-                        None
+                        if !config.ignore_errors {
+                            eprintln!("Error fetching {}: {}", config.field_name, e);
+                        }
+                        Err(CollectionError::from_failure(&args, &e))
                     }
                 }
             }
@@ -123,17 +153,24 @@ pub async fn fetch_resource_details(
         .collect();
 
     // Collect all results
-    let results: Vec<Option<(String, Value)>> = stream::iter(detail_futures)
-        .buffer_unordered(AWS_DETAIL_CONCURRENCY)
-        .collect()
-        .await;
+    let results: Vec<Result<(String, Value), Option<CollectionError>>> =
+        stream::iter(detail_futures)
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
 
     // Insert successful results into the resource object
-    for result in results.into_iter().flatten() {
-        obj.insert(result.0, result.1);
+    let mut errors = Vec::new();
+    for result in results {
+        match result {
+            Ok((field_name, value)) => {
+                obj.insert(field_name, value);
+            }
+            Err(error) => errors.extend(error),
+        }
     }
 
-    resource
+    (resource, errors)
 }
 
 // ============================================================================
@@ -150,7 +187,7 @@ pub async fn fetch_resource_details(
 /// * `commands` - Vector of (resource_type, aws_cli_args) tuples
 ///
 /// # Returns
-/// Vector of ResourceCollections (only successful ones)
+/// Vector of ResourceCollections, with failed commands recorded as errors
 pub async fn collect_resources_parallel(
     cli: &AwsCli,
     region: &str,
@@ -158,6 +195,10 @@ pub async fn collect_resources_parallel(
     timestamp: &str,
     commands: Vec<(&str, Vec<&str>)>,
 ) -> Vec<ResourceCollection> {
+    let concurrency = match commands.first() {
+        Some((_, args)) => cli.concurrency_for(args[0], AWS_COLLECTION_CONCURRENCY),
+        None => AWS_COLLECTION_CONCURRENCY,
+    };
     let futures: Vec<_> = commands
         .into_iter()
         .map(|(resource_type, args)| {
@@ -168,27 +209,23 @@ pub async fn collect_resources_parallel(
             let resource_type = resource_type.to_string();
 
             async move {
-                cli.execute(&args)
-                    .await
-                    .ok()
-                    .map(|resources| ResourceCollection {
-                        service,
-                        region,
-                        resource_type,
-                        resources,
-                        collected_at: timestamp,
-                    })
+                let result = cli.execute(&args).await;
+                ResourceCollection::from_result(
+                    &service,
+                    &region,
+                    &resource_type,
+                    &args,
+                    result,
+                    &timestamp,
+                )
             }
         })
         .collect();
 
     stream::iter(futures)
-        .buffer_unordered(AWS_COLLECTION_CONCURRENCY)
-        .collect::<Vec<_>>()
-        .await
-        .into_iter()
-        .flatten()
+        .buffer_unordered(concurrency)
         .collect()
+        .await
 }
 
 // ============================================================================
@@ -204,3 +241,98 @@ pub fn extract_array(response: &Value, key: &str) -> Option<Vec<Value>> {
 pub fn extract_string(value: &Value, key: &str) -> Option<String> {
     value.get(key)?.as_str().map(String::from)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::executor::FakeExecutor;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_collect_resources_parallel_records_failed_commands() {
+        let fake = FakeExecutor::new()
+            .respond(
+                &["rds", "describe-db-instances"],
+                json!({ "DBInstances": [] }),
+            )
+            .fail(
+                &["rds", "describe-db-clusters"],
+                "An error occurred (AccessDenied) when calling the DescribeDBClusters operation",
+            );
+        let cli = AwsCli::with_executor("default".to_string(), Arc::new(fake));
+
+        let collections = collect_resources_parallel(
+            &cli,
+            "us-east-1",
+            "rds",
+            "2026-01-01T00:00:00+00:00",
+            vec![
+                ("db-instances", vec!["rds", "describe-db-instances"]),
+                ("db-clusters", vec!["rds", "describe-db-clusters"]),
+            ],
+        )
+        .await;
+
+        assert_eq!(collections.len(), 2);
+        let instances = collections
+            .iter()
+            .find(|c| c.resource_type == "db-instances")
+            .unwrap();
+        assert!(instances.errors.is_empty());
+
+        let clusters = collections
+            .iter()
+            .find(|c| c.resource_type == "db-clusters")
+            .unwrap();
+        assert!(clusters.is_failed());
+        assert_eq!(clusters.errors[0].error_class, AwsErrorKind::AccessDenied);
+        assert_eq!(clusters.errors[0].command, "rds describe-db-clusters");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_resource_details_distinguishes_missing_config_from_denied() {
+        let fake = FakeExecutor::new()
+            .fail(
+                &["s3api", "get-bucket-cors", "--bucket", "b", "--region", "us-east-1"],
+                "An error occurred (NoSuchCORSConfiguration) when calling the GetBucketCors operation",
+            )
+            .fail(
+                &["s3api", "get-bucket-policy", "--bucket", "b", "--region", "us-east-1"],
+                "An error occurred (AccessDenied) when calling the GetBucketPolicy operation",
+            );
+        let cli = AwsCli::with_executor("default".to_string(), Arc::new(fake));
+
+        let (bucket, errors) = fetch_resource_details(
+            &cli,
+            "us-east-1",
+            json!({ "Name": "b" }),
+            vec![
+                DetailConfig::new(
+                    "CORS",
+                    vec![
+                        "s3api".into(),
+                        "get-bucket-cors".into(),
+                        "--bucket".into(),
+                        "b".into(),
+                    ],
+                ),
+                DetailConfig::new(
+                    "Policy",
+                    vec![
+                        "s3api".into(),
+                        "get-bucket-policy".into(),
+                        "--bucket".into(),
+                        "b".into(),
+                    ],
+                ),
+            ],
+        )
+        .await;
+
+        assert_eq!(bucket["CORS"], Value::Null);
+        assert!(bucket.get("Policy").is_none());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error_code.as_deref(), Some("AccessDenied"));
+    }
+}
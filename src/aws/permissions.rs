@@ -0,0 +1,271 @@
+// IAM permissions required by the collectors
+//
+// Every collector declares the AWS CLI operations it may call. This module maps
+// them to IAM actions and renders the least-privilege policy for a collection.
+
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+
+/// An AWS CLI operation, e.g. `s3api get-bucket-policy`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CliOperation {
+    /// CLI service name, e.g. `s3api`
+    pub service: String,
+    /// CLI operation name, e.g. `get-bucket-policy`
+    pub operation: String,
+}
+
+impl CliOperation {
+    pub fn new(service: impl Into<String>, operation: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            operation: operation.into(),
+        }
+    }
+
+    /// Operation of an AWS CLI argument list (`["ec2", "describe-vpcs", ...]`)
+    pub fn from_args<S: AsRef<str>>(args: &[S]) -> Option<Self> {
+        match args {
+            [service, operation, ..] => Some(Self::new(service.as_ref(), operation.as_ref())),
+            _ => None,
+        }
+    }
+
+    /// IAM action authorizing this operation, e.g. `s3:GetBucketPolicy`
+    pub fn iam_action(&self) -> String {
+        if let Some((_, _, action)) = ACTION_EXCEPTIONS.iter().find(|(service, operation, _)| {
+            *service == self.service && *operation == self.operation
+        }) {
+            return action.to_string();
+        }
+
+        let prefix = SERVICE_PREFIXES
+            .iter()
+            .find(|(service, _)| *service == self.service)
+            .map(|(_, prefix)| *prefix)
+            .unwrap_or(&self.service);
+
+        format!("{}:{}", prefix, pascal_case(&self.operation))
+    }
+}
+
+/// CLI services whose IAM prefix differs from the CLI service name
+const SERVICE_PREFIXES: &[(&str, &str)] = &[
+    ("s3api", "s3"),
+    ("elb", "elasticloadbalancing"),
+    ("elbv2", "elasticloadbalancing"),
+    ("sesv2", "ses"),
+];
+
+/// Operations whose IAM action is not named after the API operation
+const ACTION_EXCEPTIONS: &[(&str, &str, &str)] = &[
+    ("s3api", "list-buckets", "s3:ListAllMyBuckets"),
+    ("s3api", "get-bucket-cors", "s3:GetBucketCORS"),
+    ("s3api", "get-bucket-acl", "s3:GetBucketAcl"),
+    (
+        "s3api",
+        "get-bucket-encryption",
+        "s3:GetEncryptionConfiguration",
+    ),
+    (
+        "s3api",
+        "get-bucket-lifecycle-configuration",
+        "s3:GetLifecycleConfiguration",
+    ),
+    (
+        "s3api",
+        "get-bucket-replication",
+        "s3:GetReplicationConfiguration",
+    ),
+    (
+        "s3api",
+        "get-bucket-notification-configuration",
+        "s3:GetBucketNotification",
+    ),
+    (
+        "s3api",
+        "get-public-access-block",
+        "s3:GetBucketPublicAccessBlock",
+    ),
+    (
+        "s3api",
+        "get-object-lock-configuration",
+        "s3:GetBucketObjectLockConfiguration",
+    ),
+    (
+        "s3api",
+        "list-bucket-inventory-configurations",
+        "s3:GetInventoryConfiguration",
+    ),
+    (
+        "s3api",
+        "list-bucket-analytics-configurations",
+        "s3:GetAnalyticsConfiguration",
+    ),
+    (
+        "s3api",
+        "list-bucket-metrics-configurations",
+        "s3:GetMetricsConfiguration",
+    ),
+    (
+        "s3api",
+        "list-bucket-intelligent-tiering-configurations",
+        "s3:GetIntelligentTieringConfiguration",
+    ),
+    ("iam", "list-saml-providers", "iam:ListSAMLProviders"),
+    (
+        "iam",
+        "list-open-id-connect-providers",
+        "iam:ListOpenIDConnectProviders",
+    ),
+    ("rds", "describe-db-instances", "rds:DescribeDBInstances"),
+    ("rds", "describe-db-clusters", "rds:DescribeDBClusters"),
+    ("rds", "describe-db-snapshots", "rds:DescribeDBSnapshots"),
+    (
+        "rds",
+        "describe-db-cluster-snapshots",
+        "rds:DescribeDBClusterSnapshots",
+    ),
+    (
+        "rds",
+        "describe-db-subnet-groups",
+        "rds:DescribeDBSubnetGroups",
+    ),
+    (
+        "rds",
+        "describe-db-parameter-groups",
+        "rds:DescribeDBParameterGroups",
+    ),
+    (
+        "rds",
+        "describe-db-cluster-parameter-groups",
+        "rds:DescribeDBClusterParameterGroups",
+    ),
+    (
+        "rds",
+        "describe-db-security-groups",
+        "rds:DescribeDBSecurityGroups",
+    ),
+    ("rds", "describe-db-proxies", "rds:DescribeDBProxies"),
+    (
+        "rds",
+        "describe-reserved-db-instances",
+        "rds:DescribeReservedDBInstances",
+    ),
+];
+
+/// Operations every collection run performs regardless of the services
+pub fn base_operations() -> Vec<CliOperation> {
+    vec![
+        // Credential validation
+        CliOperation::new("sts", "get-caller-identity"),
+        // Region validation
+        CliOperation::new("ec2", "describe-regions"),
+    ]
+}
+
+/// Operations of runs assuming roles in other accounts (`--role-arn`, `--role-name`)
+pub fn assume_role_operations() -> Vec<CliOperation> {
+    vec![CliOperation::new("sts", "assume-role")]
+}
+
+/// Operations of `--organization` runs: listing the member accounts (by OU
+/// and tag) and assuming a role in each of them
+pub fn organization_operations() -> Vec<CliOperation> {
+    let mut operations = assume_role_operations();
+    operations.extend(
+        [
+            "list-accounts",
+            "list-accounts-for-parent",
+            "list-organizational-units-for-parent",
+            "list-tags-for-resource",
+        ]
+        .into_iter()
+        .map(|operation| CliOperation::new("organizations", operation)),
+    );
+    operations
+}
+
+/// IAM policy document allowing exactly the given operations
+pub fn policy_document(operations: &[CliOperation]) -> Value {
+    let actions: BTreeSet<String> = operations.iter().map(|op| op.iam_action()).collect();
+
+    json!({
+        "Version": "2012-10-17",
+        "Statement": [
+            {
+                "Sid": "CloudCollectorReadOnly",
+                "Effect": "Allow",
+                "Action": actions,
+                "Resource": "*"
+            }
+        ]
+    })
+}
+
+/// `describe-load-balancers` -> `DescribeLoadBalancers`
+fn pascal_case(operation: &str) -> String {
+    operation
+        .split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iam_action_mapping() {
+        let cases = [
+            ("ec2", "describe-vpcs", "ec2:DescribeVpcs"),
+            (
+                "elbv2",
+                "describe-target-health",
+                "elasticloadbalancing:DescribeTargetHealth",
+            ),
+            ("s3api", "list-buckets", "s3:ListAllMyBuckets"),
+            (
+                "s3api",
+                "get-bucket-encryption",
+                "s3:GetEncryptionConfiguration",
+            ),
+            ("s3api", "get-bucket-policy", "s3:GetBucketPolicy"),
+            ("rds", "describe-db-proxies", "rds:DescribeDBProxies"),
+            ("logs", "describe-log-groups", "logs:DescribeLogGroups"),
+            ("sts", "assume-role", "sts:AssumeRole"),
+            (
+                "organizations",
+                "list-organizational-units-for-parent",
+                "organizations:ListOrganizationalUnitsForParent",
+            ),
+        ];
+
+        for (service, operation, action) in cases {
+            assert_eq!(CliOperation::new(service, operation).iam_action(), action);
+        }
+    }
+
+    #[test]
+    fn test_policy_document_deduplicates_actions() {
+        let policy = policy_document(&[
+            CliOperation::new("elb", "describe-load-balancers"),
+            CliOperation::new("elbv2", "describe-load-balancers"),
+            CliOperation::new("ec2", "describe-vpcs"),
+        ]);
+
+        assert_eq!(
+            policy["Statement"][0]["Action"],
+            json!([
+                "ec2:DescribeVpcs",
+                "elasticloadbalancing:DescribeLoadBalancers"
+            ])
+        );
+    }
+}
@@ -0,0 +1,118 @@
+// AWS profile discovery
+//
+// `--all-profiles` enumerates the profiles of the shared config and
+// credentials files, honoring `AWS_CONFIG_FILE` and
+// `AWS_SHARED_CREDENTIALS_FILE` like the AWS CLI does.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+/// Names of every profile defined in the shared config and credentials files
+pub fn discover_profiles() -> Result<Vec<String>> {
+    let config = read_optional(config_file("AWS_CONFIG_FILE", "config")?)?;
+    let credentials = read_optional(config_file("AWS_SHARED_CREDENTIALS_FILE", "credentials")?)?;
+
+    let profiles = parse_profiles(&config, &credentials);
+    if profiles.is_empty() {
+        anyhow::bail!("No AWS profiles found in the shared config or credentials files");
+    }
+
+    Ok(profiles)
+}
+
+/// Profile names of a config file and a credentials file, sorted and deduplicated
+///
+/// In the config file profiles are `[default]` and `[profile name]`; other
+/// sections such as `[sso-session name]` or `[services name]` are shared
+/// settings, not profiles. In the credentials file every section is a profile.
+fn parse_profiles(config: &str, credentials: &str) -> Vec<String> {
+    let mut profiles = BTreeSet::new();
+
+    for section in sections(config) {
+        if section == "default" {
+            profiles.insert(section.to_string());
+        } else if let Some(name) = section.strip_prefix("profile ") {
+            profiles.insert(name.trim().to_string());
+        }
+    }
+
+    profiles.extend(sections(credentials).map(String::from));
+
+    profiles
+        .into_iter()
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Section headers (`[...]`) of an INI file, trimmed
+fn sections(contents: &str) -> impl Iterator<Item = &str> {
+    contents.lines().filter_map(|line| {
+        line.trim()
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+            .map(str::trim)
+    })
+}
+
+/// Path of a shared file: the environment override or `~/.aws/{name}`
+fn config_file(env_var: &str, name: &str) -> Result<PathBuf> {
+    if let Some(path) = std::env::var_os(env_var) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .context("Cannot locate the home directory to read AWS profiles")?;
+    Ok(PathBuf::from(home).join(".aws").join(name))
+}
+
+/// Contents of a file, or an empty string if it does not exist
+fn read_optional(path: PathBuf) -> Result<String> {
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => Ok(contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e).context(format!("Failed to read {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profiles_skips_shared_sections() {
+        let config = "\
+[default]
+region = us-east-1
+
+[profile prod]
+sso_session = corp
+sso_account_id = 111111111111
+sso_role_name = ReadOnly
+
+[sso-session corp]
+sso_start_url = https://corp.awsapps.com/start
+sso_region = us-east-1
+
+[services local]
+s3 =
+  endpoint_url = http://localhost:4566
+
+[ profile  staging ]
+region = eu-west-1
+";
+        let credentials = "\
+[default]
+aws_access_key_id = AKIA
+
+[ci]
+aws_access_key_id = AKIA
+";
+
+        assert_eq!(
+            parse_profiles(config, credentials),
+            vec!["ci", "default", "prod", "staging"]
+        );
+    }
+}
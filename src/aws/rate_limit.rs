@@ -0,0 +1,209 @@
+// Rate limiting of AWS API calls
+//
+// Every call made through `AwsCli` takes a token from a token bucket keyed by
+// CLI service, or by `service:operation` when that operation has its own limit.
+// The buckets are shared by all collectors of a run, so the request rate per
+// API stays bounded however many collectors and detail fetches run in parallel.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Calls per second by service or operation
+///
+/// Configured with `--rate-limit` (`RATE`, `SERVICE=RATE` or
+/// `SERVICE:OPERATION=RATE`) or a JSON file:
+///
+/// ```json
+/// { "default": 10, "limits": { "ec2": 5, "ec2:describe-snapshots": 1 } }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimits {
+    /// Rate of every service without its own limit
+    #[serde(default)]
+    pub default: Option<f64>,
+    /// Rate per `service` or `service:operation`
+    #[serde(default)]
+    pub limits: HashMap<String, f64>,
+}
+
+impl RateLimits {
+    /// Load limits from a JSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .context(format!("Failed to read rate limits {}", path.display()))?;
+        let limits: Self = serde_json::from_str(&contents)
+            .context(format!("Failed to parse rate limits {}", path.display()))?;
+        limits.validate()?;
+        Ok(limits)
+    }
+
+    /// Apply a `--rate-limit` value, overriding any limit of the same key
+    pub fn apply(&mut self, spec: &str) -> Result<()> {
+        let (key, rate) = match spec.split_once('=') {
+            Some((key, rate)) => (Some(key.trim()), rate),
+            None => (None, spec),
+        };
+        let rate: f64 = rate
+            .trim()
+            .parse()
+            .context(format!("Invalid rate limit '{}'", spec))?;
+
+        match key {
+            Some(key) => {
+                self.limits.insert(key.to_string(), rate);
+            }
+            None => self.default = Some(rate),
+        }
+        self.validate()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.default.is_none() && self.limits.is_empty()
+    }
+
+    fn validate(&self) -> Result<()> {
+        for rate in self.default.iter().chain(self.limits.values()) {
+            if !rate.is_finite() || *rate <= 0.0 {
+                anyhow::bail!(
+                    "Rate limits must be positive calls per second, got {}",
+                    rate
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Bucket key and rate governing an operation, if it is limited
+    fn limit_for(&self, service: &str, operation: &str) -> Option<(String, f64)> {
+        let operation_key = format!("{}:{}", service, operation);
+        if let Some(rate) = self.limits.get(&operation_key) {
+            return Some((operation_key, *rate));
+        }
+        self.limits
+            .get(service)
+            .copied()
+            .or(self.default)
+            .map(|rate| (service.to_string(), rate))
+    }
+}
+
+/// Token bucket refilled continuously at `rate` tokens per second
+struct Bucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl Bucket {
+    fn new(rate: f64) -> Self {
+        // Allow a burst of one second's worth of calls
+        let capacity = rate.max(1.0);
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    /// Take a token, returning how long the caller must wait before using it
+    ///
+    /// Tokens may go negative: each waiting caller reserves the next token, so
+    /// waiters are served in order without polling.
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.refilled_at = now;
+
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+/// Token buckets shared by every client of a run
+#[derive(Default)]
+pub struct RateLimiter {
+    limits: RateLimits,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait until a call to `service operation` is allowed
+    pub async fn acquire(&self, service: &str, operation: &str) {
+        let Some((key, rate)) = self.limits.limit_for(service, operation) else {
+            return;
+        };
+
+        let wait = self
+            .buckets
+            .lock()
+            .await
+            .entry(key)
+            .or_insert_with(|| Bucket::new(rate))
+            .reserve(Instant::now());
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_limit_takes_precedence_over_service_and_default() {
+        let mut limits = RateLimits::default();
+        limits.apply("10").unwrap();
+        limits.apply("ec2=5").unwrap();
+        limits.apply("ec2:describe-snapshots=0.5").unwrap();
+
+        assert_eq!(
+            limits.limit_for("ec2", "describe-snapshots"),
+            Some(("ec2:describe-snapshots".to_string(), 0.5))
+        );
+        assert_eq!(
+            limits.limit_for("ec2", "describe-vpcs"),
+            Some(("ec2".to_string(), 5.0))
+        );
+        assert_eq!(
+            limits.limit_for("s3api", "list-buckets"),
+            Some(("s3api".to_string(), 10.0))
+        );
+        assert!(limits.apply("ec2=0").is_err());
+    }
+
+    #[test]
+    fn test_bucket_spaces_calls_beyond_the_burst() {
+        let start = Instant::now();
+        let mut bucket = Bucket::new(2.0);
+
+        // Burst of two calls, then one call every 500ms
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::from_millis(500));
+        assert_eq!(bucket.reserve(start), Duration::from_millis(1000));
+
+        // Refilled after waiting
+        let later = start + Duration::from_secs(5);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+    }
+}
@@ -1,52 +1,176 @@
 use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
 
 use super::cli::AwsCli;
 
-/// Validate AWS regions by querying AWS CLI
-pub async fn validate_regions(cli: &AwsCli, regions: &[String]) -> Result<()> {
-    // Get list of valid regions from AWS
-    let valid_regions = get_valid_regions(cli).await?;
+/// Region of a profile that configures none, for every backend
+pub const DEFAULT_REGION: &str = "ap-southeast-1";
+
+/// DNS suffix of service endpoints in a region, e.g. `amazonaws.com.cn` for
+/// the China regions
+pub fn dns_suffix(region: &str) -> &'static str {
+    if region.starts_with("cn-") {
+        "amazonaws.com.cn"
+    } else if region.starts_with("us-isob-") {
+        "sc2s.sgov.gov"
+    } else if region.starts_with("us-isof-") {
+        "csp.hci.ic.gov"
+    } else if region.starts_with("us-iso-") {
+        "c2s.ic.gov"
+    } else if region.starts_with("eu-isoe-") {
+        "cloud.adc-e.uk"
+    } else {
+        "amazonaws.com"
+    }
+}
+
+/// Opt-in status of a region the account has not enabled
+const NOT_OPTED_IN: &str = "not-opted-in";
+
+/// Every region of the partition with its opt-in status
+///
+/// Queried once per profile with `ec2 describe-regions --all-regions` and
+/// reused for both `--all-regions` and region validation.
+#[derive(Debug, Clone)]
+pub struct RegionCatalog {
+    /// `(region, OptInStatus)`, sorted by region
+    regions: Vec<(String, String)>,
+}
+
+impl RegionCatalog {
+    /// Query the regions available to the account
+    pub async fn load(cli: &AwsCli) -> Result<Self> {
+        let json = cli
+            .execute(&["ec2", "describe-regions", "--all-regions"])
+            .await
+            .context("Failed to query AWS regions")?;
+
+        Self::from_response(&json)
+    }
+
+    fn from_response(json: &Value) -> Result<Self> {
+        let mut regions: Vec<(String, String)> = json["Regions"]
+            .as_array()
+            .context("Failed to parse regions from AWS response")?
+            .iter()
+            .filter_map(|region| {
+                let name = region["RegionName"].as_str()?;
+                let status = region["OptInStatus"]
+                    .as_str()
+                    .unwrap_or("opt-in-not-required");
+                Some((name.to_string(), status.to_string()))
+            })
+            .collect();
+        regions.sort();
+
+        Ok(Self { regions })
+    }
+
+    /// Regions the account can use
+    pub fn enabled(&self) -> Vec<String> {
+        self.regions
+            .iter()
+            .filter(|(_, status)| status != NOT_OPTED_IN)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Opt-in regions the account has not enabled
+    pub fn opted_out(&self) -> Vec<String> {
+        self.regions
+            .iter()
+            .filter(|(_, status)| status == NOT_OPTED_IN)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+/// Validate AWS regions against the region catalog
+///
+/// A region that exists but is not enabled for the account is reported
+/// separately from an unknown (misspelled) region.
+pub fn validate_regions(catalog: &RegionCatalog, regions: &[String]) -> Result<()> {
+    let valid_regions = catalog.enabled();
+    let opted_out = catalog.opted_out();
 
     // Check each region
     for region in regions {
-        if !valid_regions.contains(region) {
-            // Find similar regions for helpful error message
-            let similar: Vec<String> = valid_regions
-                .iter()
-                .filter(|r| {
-                    r.starts_with(&region[..region.len().min(3)])
-                        || region.starts_with(&r[..r.len().min(3)])
-                })
-                .take(5)
-                .cloned()
-                .collect();
-
-            let suggestion = if !similar.is_empty() {
-                format!(
-                    "\n\n💡 Did you mean one of these?\n   {}",
-                    similar.join(", ")
-                )
-            } else {
-                let sample: Vec<String> = valid_regions.iter().take(10).cloned().collect();
-                format!("\n\n💡 Available regions:\n   {}", sample.join(", "))
-            };
-
-            return Err(anyhow!("❌ Invalid AWS region: '{}'{}", region, suggestion));
+        if valid_regions.contains(region) {
+            continue;
+        }
+
+        if opted_out.contains(region) {
+            return Err(anyhow!(
+                "❌ AWS region '{}' is not enabled for this account (opt-in required)\n\n💡 Enable it in the AWS account settings or remove it from --regions",
+                region
+            ));
         }
+
+        // Find similar regions for helpful error message
+        let similar: Vec<String> = valid_regions
+            .iter()
+            .filter(|r| {
+                r.starts_with(&region[..region.len().min(3)])
+                    || region.starts_with(&r[..r.len().min(3)])
+            })
+            .take(5)
+            .cloned()
+            .collect();
+
+        let suggestion = if !similar.is_empty() {
+            format!(
+                "\n\n💡 Did you mean one of these?\n   {}",
+                similar.join(", ")
+            )
+        } else {
+            let sample: Vec<String> = valid_regions.iter().take(10).cloned().collect();
+            format!("\n\n💡 Available regions:\n   {}", sample.join(", "))
+        };
+
+        return Err(anyhow!("❌ Invalid AWS region: '{}'{}", region, suggestion));
     }
 
     Ok(())
 }
 
-/// Get list of valid AWS regions from AWS CLI
-async fn get_valid_regions(cli: &AwsCli) -> Result<Vec<String>> {
-    let json = cli
-        .execute(&["ec2", "describe-regions", "--query", "Regions[].RegionName"])
-        .await
-        .context("Failed to query AWS regions")?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
 
-    let regions: Vec<String> =
-        serde_json::from_value(json).context("Failed to parse regions from AWS response")?;
+    fn catalog() -> RegionCatalog {
+        RegionCatalog::from_response(&json!({ "Regions": [
+            { "RegionName": "us-east-1", "OptInStatus": "opt-in-not-required" },
+            { "RegionName": "ap-east-1", "OptInStatus": "not-opted-in" },
+            { "RegionName": "me-south-1", "OptInStatus": "opted-in" },
+        ]}))
+        .unwrap()
+    }
 
-    Ok(regions)
+    #[test]
+    fn test_dns_suffix_per_partition() {
+        assert_eq!(dns_suffix("us-east-1"), "amazonaws.com");
+        assert_eq!(dns_suffix("us-gov-west-1"), "amazonaws.com");
+        assert_eq!(dns_suffix("cn-north-1"), "amazonaws.com.cn");
+        assert_eq!(dns_suffix("us-isob-east-1"), "sc2s.sgov.gov");
+    }
+
+    #[test]
+    fn test_catalog_splits_enabled_and_opted_out_regions() {
+        let catalog = catalog();
+        assert_eq!(catalog.enabled(), vec!["me-south-1", "us-east-1"]);
+        assert_eq!(catalog.opted_out(), vec!["ap-east-1"]);
+    }
+
+    #[test]
+    fn test_validate_regions_tells_opted_out_from_typos() {
+        let catalog = catalog();
+        assert!(validate_regions(&catalog, &["us-east-1".to_string()]).is_ok());
+
+        let opted_out = validate_regions(&catalog, &["ap-east-1".to_string()]).unwrap_err();
+        assert!(opted_out.to_string().contains("not enabled"));
+
+        let typo = validate_regions(&catalog, &["us-eats-1".to_string()]).unwrap_err();
+        assert!(typo.to_string().contains("Invalid AWS region"));
+    }
 }
@@ -0,0 +1,157 @@
+// Retry policy for AWS CLI invocations
+//
+// Failed invocations are classified from their typed `AwsError`. Throttling and
+// transient service errors are retried with full-jitter exponential backoff;
+// everything else fails immediately.
+
+use rand::Rng;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use super::error::{AwsError, AwsErrorKind};
+
+/// How a failed invocation should be handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureClass {
+    Throttled,
+    Transient,
+    Permanent,
+}
+
+/// Classify a failed invocation for retrying
+pub fn classify_failure(error: &AwsError) -> FailureClass {
+    match error.kind {
+        AwsErrorKind::Throttled => FailureClass::Throttled,
+        AwsErrorKind::ServiceUnavailable => FailureClass::Transient,
+        _ => FailureClass::Permanent,
+    }
+}
+
+/// Retry budget and backoff parameters
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts per call, including the first one
+    pub max_attempts: u32,
+    /// Backoff cap for the first retry, doubled on each subsequent retry
+    pub base_delay: Duration,
+    /// Upper bound for a single backoff
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(20),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy with the default backoff and the given attempt budget
+    pub fn with_max_attempts(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// Full-jitter backoff before retry number `retry` (starting at 1)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(16);
+        let cap = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+        let millis = cap.as_millis() as u64;
+        Duration::from_millis(rand::thread_rng().gen_range(0..=millis))
+    }
+}
+
+/// Run-wide retry counters, shared between clones of `AwsCli`
+#[derive(Debug, Default)]
+pub struct RetryStats {
+    retries: AtomicU64,
+    throttled: AtomicU64,
+    exhausted: AtomicU64,
+}
+
+/// Point-in-time copy of `RetryStats`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetrySummary {
+    /// Number of retried attempts
+    pub retries: u64,
+    /// Number of attempts that were throttled
+    pub throttled: u64,
+    /// Number of calls that still failed after the last attempt
+    pub exhausted: u64,
+}
+
+impl RetryStats {
+    pub fn record_retry(&self, class: FailureClass) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+        if class == FailureClass::Throttled {
+            self.throttled.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn record_exhausted(&self, class: FailureClass) {
+        self.exhausted.fetch_add(1, Ordering::Relaxed);
+        if class == FailureClass::Throttled {
+            self.throttled.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn summary(&self) -> RetrySummary {
+        RetrySummary {
+            retries: self.retries.load(Ordering::Relaxed),
+            throttled: self.throttled.load(Ordering::Relaxed),
+            exhausted: self.exhausted.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::executor::CliOutput;
+
+    fn classify(stderr: &str) -> FailureClass {
+        classify_failure(&AwsError::from_output(&CliOutput {
+            exit_code: 254,
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+        }))
+    }
+
+    #[test]
+    fn test_classify_failure() {
+        assert_eq!(
+            classify(
+                "An error occurred (RequestLimitExceeded) when calling the DescribeInstances operation: Request limit exceeded."
+            ),
+            FailureClass::Throttled
+        );
+        assert_eq!(
+            classify("An error occurred (ServiceUnavailable) when calling the ListUsers operation"),
+            FailureClass::Transient
+        );
+        assert_eq!(
+            classify("Read timeout on endpoint URL: \"https://ec2.us-east-1.amazonaws.com/\""),
+            FailureClass::Transient
+        );
+        assert_eq!(
+            classify("An error occurred (AccessDenied) when calling the ListUsers operation"),
+            FailureClass::Permanent
+        );
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy = RetryPolicy::default();
+        for retry in 1..40 {
+            assert!(policy.backoff(retry) <= policy.max_delay);
+        }
+    }
+}
@@ -0,0 +1,252 @@
+// AWS Signature Version 4 request signing
+//
+// Implements the canonical request / string-to-sign / signing-key steps from
+// the AWS documentation so the native HTTP backend can call AWS APIs without
+// the `aws` binary.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Static or temporary AWS credentials
+#[derive(Debug, Clone, Deserialize)]
+pub struct Credentials {
+    #[serde(rename = "AccessKeyId")]
+    pub access_key_id: String,
+    #[serde(rename = "SecretAccessKey")]
+    pub secret_access_key: String,
+    #[serde(rename = "SessionToken", default)]
+    pub session_token: Option<String>,
+    /// When temporary credentials expire
+    #[serde(rename = "Expiration", default)]
+    pub expiration: Option<DateTime<Utc>>,
+}
+
+/// Renew temporary credentials this long before they expire
+const REFRESH_MARGIN: chrono::TimeDelta = chrono::TimeDelta::minutes(5);
+
+impl Credentials {
+    /// Whether the credentials expire within the refresh margin and should be
+    /// renewed; static credentials never do
+    pub fn expiring(&self) -> bool {
+        self.expiration
+            .is_some_and(|expiration| expiration - REFRESH_MARGIN <= Utc::now())
+    }
+}
+
+/// A request to be signed
+pub struct SignableRequest<'a> {
+    pub method: &'a str,
+    /// Absolute path, e.g. `/`
+    pub path: &'a str,
+    /// Query parameters (unencoded)
+    pub query: &'a [(String, String)],
+    /// Headers to sign; must include `host`
+    pub headers: &'a [(String, String)],
+    pub body: &'a [u8],
+}
+
+/// Scope of a signature
+pub struct SigningParams<'a> {
+    pub credentials: &'a Credentials,
+    pub region: &'a str,
+    pub service: &'a str,
+    pub time: DateTime<Utc>,
+}
+
+/// Compute the headers to add to a request: `x-amz-date`, optionally
+/// `x-amz-security-token`, and `authorization`
+pub fn sign(request: &SignableRequest, params: &SigningParams) -> Vec<(String, String)> {
+    let amz_date = params.time.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = params.time.format("%Y%m%d").to_string();
+
+    let mut extra_headers = vec![("x-amz-date".to_string(), amz_date.clone())];
+    if let Some(token) = &params.credentials.session_token {
+        extra_headers.push(("x-amz-security-token".to_string(), token.clone()));
+    }
+
+    // Canonical headers: lowercase names, trimmed values, sorted by name
+    let mut headers: Vec<(String, String)> = request
+        .headers
+        .iter()
+        .chain(extra_headers.iter())
+        .map(|(k, v)| (k.to_lowercase(), v.trim().to_string()))
+        .collect();
+    headers.sort();
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(k, v)| format!("{}:{}\n", k, v))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(k, _)| k.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        request.method,
+        canonical_uri(request.path),
+        canonical_query(request.query),
+        canonical_headers,
+        signed_headers,
+        hex_sha256(request.body)
+    );
+
+    let scope = format!("{}/{}/{}/aws4_request", date, params.region, params.service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex_sha256(canonical_request.as_bytes())
+    );
+
+    let key = signing_key(
+        &params.credentials.secret_access_key,
+        &date,
+        params.region,
+        params.service,
+    );
+    let signature = hex(&hmac(&key, string_to_sign.as_bytes()));
+
+    extra_headers.push((
+        "authorization".to_string(),
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            params.credentials.access_key_id, scope, signed_headers, signature
+        ),
+    ));
+
+    extra_headers
+}
+
+/// Derive the signing key for a date/region/service scope
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let k_date = hmac(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let k_region = hmac(&k_date, region.as_bytes());
+    let k_service = hmac(&k_region, service.as_bytes());
+    hmac(&k_service, b"aws4_request")
+}
+
+fn canonical_uri(path: &str) -> String {
+    if path.is_empty() {
+        return "/".to_string();
+    }
+    path.split('/')
+        .map(uri_encode)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn canonical_query(query: &[(String, String)]) -> String {
+    let mut pairs: Vec<(String, String)> = query
+        .iter()
+        .map(|(k, v)| (uri_encode(k), uri_encode(v)))
+        .collect();
+    pairs.sort();
+    pairs
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Percent-encode everything except unreserved characters (RFC 3986)
+fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex_sha256(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn example_credentials() -> Credentials {
+        Credentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+            expiration: None,
+        }
+    }
+
+    #[test]
+    fn test_signing_key_matches_aws_example() {
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20150830",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex(&key),
+            "c4afb1cc5771d871763a393e44b703571b55cc28424d1a5e86da6ed3c154a4b9"
+        );
+    }
+
+    #[test]
+    fn test_sign_matches_aws_example() {
+        let credentials = example_credentials();
+        let query = vec![
+            ("Action".to_string(), "ListUsers".to_string()),
+            ("Version".to_string(), "2010-05-08".to_string()),
+        ];
+        let headers = vec![
+            (
+                "Content-Type".to_string(),
+                "application/x-www-form-urlencoded; charset=utf-8".to_string(),
+            ),
+            ("Host".to_string(), "iam.amazonaws.com".to_string()),
+        ];
+
+        let signed = sign(
+            &SignableRequest {
+                method: "GET",
+                path: "/",
+                query: &query,
+                headers: &headers,
+                body: b"",
+            },
+            &SigningParams {
+                credentials: &credentials,
+                region: "us-east-1",
+                service: "iam",
+                time: Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap(),
+            },
+        );
+
+        let authorization = &signed.iter().find(|(k, _)| k == "authorization").unwrap().1;
+        assert_eq!(
+            authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+}
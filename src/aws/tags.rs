@@ -0,0 +1,141 @@
+// Tag normalization
+//
+// AWS APIs report tags in several shapes, and detail fetches nest the whole
+// tagging response under the resource (`Tags: { TagSet: [...] }` for S3,
+// `Tags: { ResourceTagSet: { Tags: [...] } }` for Route 53...). `extract_tags`
+// flattens all of them into one `key -> value` map.
+
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Keys under which a resource holds its tags
+const RESOURCE_KEYS: &[&str] = &["Tags", "TagSet", "TagList", "tags"];
+
+/// Keys under which a tagging response holds its tags
+const RESPONSE_KEYS: &[&str] = &[
+    "Tags",
+    "TagSet",
+    "TagList",
+    "tags",
+    "Items",
+    "TagDescriptions",
+    "ResourceTagSet",
+];
+
+/// Tags of a resource, in any of the shapes the collectors produce:
+///
+/// - `Tags: [{Key, Value}]` (EC2, CloudFormation, Secrets Manager...)
+/// - `TagSet: [{Key, Value}]` (`s3api get-bucket-tagging`, network interfaces)
+/// - `Tags: {key: value}` (`sqs list-queue-tags`)
+/// - `TagList: [{Key, Value}]` (RDS), `TagDescriptions[].Tags` (`elbv2 describe-tags`)
+///   and `Tags.Items` (CloudFront)
+/// - `tags: [{key, value}]` (ECS)
+pub fn extract_tags(item: &Value) -> BTreeMap<String, String> {
+    let mut tags = BTreeMap::new();
+    if let Some(object) = item.as_object() {
+        collect_wrapped(object, RESOURCE_KEYS, &mut tags);
+    }
+    tags
+}
+
+/// Tags under the given keys of an object
+fn collect_wrapped(
+    object: &Map<String, Value>,
+    keys: &[&str],
+    tags: &mut BTreeMap<String, String>,
+) {
+    for key in keys {
+        if let Some(value) = object.get(*key) {
+            collect(value, tags);
+        }
+    }
+}
+
+fn collect(value: &Value, tags: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Array(entries) => {
+            for entry in entries {
+                if let Some((key, value)) = tag_pair(entry) {
+                    tags.insert(key, value);
+                } else if let Some(object) = entry.as_object() {
+                    // `TagDescriptions` entries wrap the tags of one resource
+                    collect_wrapped(object, RESPONSE_KEYS, tags);
+                }
+            }
+        }
+        Value::Object(object) => {
+            if RESPONSE_KEYS.iter().any(|key| object.contains_key(*key)) {
+                collect_wrapped(object, RESPONSE_KEYS, tags);
+            } else {
+                // A plain `{key: value}` map
+                tags.extend(
+                    object.iter().filter_map(|(key, value)| {
+                        Some((key.clone(), value.as_str()?.to_string()))
+                    }),
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `{Key, Value}` or `{key, value}`; a tag without a value maps to ""
+fn tag_pair(entry: &Value) -> Option<(String, String)> {
+    let key = entry.get("Key").or_else(|| entry.get("key"))?.as_str()?;
+    let value = entry
+        .get("Value")
+        .or_else(|| entry.get("value"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    Some((key.to_string(), value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn prod(tags: BTreeMap<String, String>) -> bool {
+        tags.get("env").map(String::as_str) == Some("prod") && tags.len() == 1
+    }
+
+    #[test]
+    fn test_extract_tags_from_every_shape() {
+        // EC2
+        assert!(prod(extract_tags(
+            &json!({ "Tags": [{ "Key": "env", "Value": "prod" }] })
+        )));
+        // S3 get-bucket-tagging, stored as the `Tags` detail
+        assert!(prod(extract_tags(
+            &json!({ "Tags": { "TagSet": [{ "Key": "env", "Value": "prod" }] } })
+        )));
+        // SQS list-queue-tags
+        assert!(prod(extract_tags(
+            &json!({ "Tags": { "Tags": { "env": "prod" } } })
+        )));
+        // RDS
+        assert!(prod(extract_tags(
+            &json!({ "TagList": [{ "Key": "env", "Value": "prod" }] })
+        )));
+        // elbv2 describe-tags
+        assert!(prod(extract_tags(&json!({ "Tags": { "TagDescriptions": [
+            { "ResourceArn": "arn:aws:elasticloadbalancing:...", "Tags": [{ "Key": "env", "Value": "prod" }] }
+        ]}}))));
+        // CloudFront list-tags-for-resource
+        assert!(prod(extract_tags(
+            &json!({ "Tags": { "Tags": { "Items": [{ "Key": "env", "Value": "prod" }] } } })
+        )));
+        // Route 53 list-tags-for-resource
+        assert!(prod(extract_tags(&json!({ "Tags": { "ResourceTagSet": {
+            "ResourceType": "hostedzone",
+            "Tags": [{ "Key": "env", "Value": "prod" }]
+        }}}))));
+        // ECS
+        assert!(prod(extract_tags(
+            &json!({ "tags": [{ "key": "env", "value": "prod" }] })
+        )));
+
+        assert!(extract_tags(&json!({ "InstanceId": "i-0abc" })).is_empty());
+        assert!(extract_tags(&json!({ "Tags": {} })).is_empty());
+    }
+}
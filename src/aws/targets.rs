@@ -0,0 +1,177 @@
+// Collection targets
+//
+// A run collects one or more AWS accounts. Without roles the target is the
+// account of the profile's own credentials; with roles every role is assumed
+// from the profile and its account becomes a separate target.
+
+use anyhow::Result;
+
+use super::arn::Arn;
+use super::cli::AwsCli;
+use super::error::AwsErrorKind;
+use crate::models::CollectionFailure;
+
+/// An account to collect, with the client whose credentials reach it
+#[derive(Clone)]
+pub struct CollectionTarget {
+    pub cli: AwsCli,
+    pub account_id: String,
+    /// Partition of the account, e.g. `aws` or `aws-cn`
+    pub partition: String,
+}
+
+/// ARN of the role named `role_name` in `account_id`
+///
+/// The name may include a path, e.g. `audit/ReadOnly`. `partition` is the
+/// partition of the caller (see `AwsCli::caller_arn`), since roles can only be
+/// assumed within it.
+pub fn role_arn_for(partition: &str, account_id: &str, role_name: &str) -> String {
+    let role = format!("role/{}", role_name.trim_start_matches('/'));
+    Arn::new(partition, "iam", "", account_id.trim(), &role).to_string()
+}
+
+/// Parsed IAM role ARN (`arn:aws:iam::123456789012:role/Name`), whose
+/// account and partition are those of the role's target
+fn parse_role(role_arn: &str) -> Option<Arn> {
+    let arn: Arn = role_arn.parse().ok()?;
+    let is_role = arn.service == "iam" && arn.resource_type() == Some("role");
+    (is_role && !arn.account.is_empty()).then_some(arn)
+}
+
+/// Target for the account of the client's credentials
+async fn target_of(cli: AwsCli) -> Result<CollectionTarget> {
+    let caller = cli.caller_arn().await?;
+    Ok(CollectionTarget {
+        cli,
+        account_id: caller.account,
+        partition: caller.partition,
+    })
+}
+
+/// Target for the account of the client's own credentials
+pub async fn caller_target(cli: &AwsCli) -> Result<CollectionTarget> {
+    target_of(cli.clone()).await
+}
+
+/// Assume every role, returning the reachable targets and the failed roles
+pub async fn assume_targets(
+    cli: &AwsCli,
+    role_arns: &[String],
+    default_region: &str,
+) -> Result<(Vec<CollectionTarget>, Vec<CollectionFailure>)> {
+    let mut targets = Vec::new();
+    let mut failures = Vec::new();
+
+    for role_arn in role_arns {
+        match cli.assume_role(role_arn, default_region).await {
+            Ok(role_cli) => targets.push(match parse_role(role_arn) {
+                Some(role) => CollectionTarget {
+                    cli: role_cli,
+                    account_id: role.account,
+                    partition: role.partition,
+                },
+                None => target_of(role_cli).await?,
+            }),
+            Err(e) => {
+                eprintln!("⚠️  Skipping {}: {:#}", role_arn, e);
+                failures.push(CollectionFailure::new(
+                    format!("assume-role {}", role_arn),
+                    AwsErrorKind::of(&e),
+                ));
+            }
+        }
+    }
+
+    Ok((targets, failures))
+}
+
+/// Resolve the targets of a run
+///
+/// Roles that cannot be assumed are reported in the returned failures and
+/// skipped; it is an error if none of them can be assumed.
+pub async fn resolve_targets(
+    cli: &AwsCli,
+    role_arns: &[String],
+    default_region: &str,
+) -> Result<(Vec<CollectionTarget>, Vec<CollectionFailure>)> {
+    if role_arns.is_empty() {
+        return Ok((vec![caller_target(cli).await?], Vec::new()));
+    }
+
+    let (targets, failures) = assume_targets(cli, role_arns, default_region).await?;
+    if targets.is_empty() {
+        anyhow::bail!("None of the {} roles could be assumed", role_arns.len());
+    }
+
+    Ok((targets, failures))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::executor::FakeExecutor;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_role_arn_for_account() {
+        assert_eq!(
+            role_arn_for("aws", "123456789012", "audit/ReadOnly"),
+            "arn:aws:iam::123456789012:role/audit/ReadOnly"
+        );
+        assert_eq!(
+            role_arn_for("aws-us-gov", "123456789012", "ReadOnly"),
+            "arn:aws-us-gov:iam::123456789012:role/ReadOnly"
+        );
+        let role = parse_role("arn:aws-cn:iam::123456789012:role/ReadOnly").unwrap();
+        assert_eq!(role.account, "123456789012");
+        assert_eq!(role.partition, "aws-cn");
+        assert_eq!(parse_role("arn:aws:iam::123456789012:user/alice"), None);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_targets_skips_roles_that_cannot_be_assumed() {
+        let ok = "arn:aws:iam::111111111111:role/Audit";
+        let denied = "arn:aws:iam::222222222222:role/Audit";
+        let fake = Arc::new(
+            FakeExecutor::new()
+                .respond(
+                    &[
+                        "sts",
+                        "assume-role",
+                        "--role-arn",
+                        ok,
+                        "--role-session-name",
+                        "cloud-collector",
+                    ],
+                    json!({ "Credentials": {
+                        "AccessKeyId": "ASIA",
+                        "SecretAccessKey": "secret",
+                        "SessionToken": "token",
+                    }}),
+                )
+                .fail(
+                    &[
+                        "sts",
+                        "assume-role",
+                        "--role-arn",
+                        denied,
+                        "--role-session-name",
+                        "cloud-collector",
+                    ],
+                    "An error occurred (AccessDenied) when calling the AssumeRole operation",
+                ),
+        );
+        let cli = AwsCli::with_executor("default".to_string(), fake);
+
+        let (targets, failures) =
+            resolve_targets(&cli, &[ok.to_string(), denied.to_string()], "us-east-1")
+                .await
+                .unwrap();
+
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].account_id, "111111111111");
+        assert_eq!(targets[0].cli.role_arn(), Some(ok));
+        assert_eq!(failures.len(), 1);
+    }
+}
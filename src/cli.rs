@@ -1,4 +1,5 @@
-use clap::Parser;
+use clap::{ArgGroup, Parser};
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(name = "cloud_collector")]
@@ -26,30 +27,268 @@ pub enum Provider {
 #[derive(Parser, Debug)]
 pub enum AwsCommands {
     /// Collect AWS resources
-    Collect {
-        /// AWS profile to use
-        #[arg(short, long, default_value = "default")]
-        profile: String,
+    Collect(Box<CollectArgs>),
 
-        /// Additional regions to collect from (comma-separated)
-        #[arg(short, long)]
-        regions: Option<String>,
+    /// List all available AWS services
+    #[command(alias = "ls")]
+    ListServices,
 
-        /// Services to collect from additional regions (comma-separated)
-        /// If not specified, all services will be collected from additional regions
-        #[arg(short = 's', long, requires = "regions")]
-        region_services: Option<String>,
+    /// Print the least-privilege IAM policy needed to collect the given services
+    RequiredPermissions {
+        /// Services to include (comma-separated); all services if not specified
+        #[arg(short, long)]
+        services: Option<String>,
 
-        /// Create new timestamped files instead of overwriting existing ones
-        #[arg(short = 'n', long, default_value = "false")]
-        create_new_file: bool,
+        /// Include the calls of runs assuming roles (`--role-arn`, `--role-name`)
+        #[arg(long)]
+        assume_role: bool,
 
-        /// Maximum number of concurrent collectors (1-10)
-        #[arg(short = 'j', long, default_value = "5")]
-        concurrency: usize,
+        /// Include the calls of `--organization` runs (account discovery and
+        /// assuming roles)
+        #[arg(long)]
+        organization: bool,
     },
 
-    /// List all available AWS services
-    #[command(alias = "ls")]
-    ListServices,
+    /// Render the resource relationships of a collected snapshot as a diagram
+    ExportGraph(ExportGraphArgs),
+}
+
+/// Arguments of `aws export-graph`
+#[derive(clap::Args, Debug)]
+pub struct ExportGraphArgs {
+    /// Profile whose snapshot in `{output-dir}/{profile}` to render
+    #[arg(short, long, default_value = "default")]
+    pub profile: String,
+
+    /// Directory the snapshot was collected to
+    #[arg(long, default_value = "./output")]
+    pub output_dir: PathBuf,
+
+    /// Diagram format
+    #[arg(short, long, value_enum, default_value = "dot")]
+    pub format: GraphFormat,
+
+    /// Only resources in these VPCs (comma-separated VPC IDs)
+    #[arg(long, value_delimiter = ',')]
+    pub vpc: Vec<String>,
+
+    /// Only resources in these regions (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    pub region: Vec<String>,
+
+    /// Only resources carrying this tag (KEY=VALUE, repeatable)
+    #[arg(long, value_parser = parse_tag)]
+    pub tag: Vec<(String, String)>,
+
+    /// Only resources of these services (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    pub service: Vec<String>,
+
+    /// Group resources into clusters by network location
+    #[arg(long, value_enum, default_value = "vpc")]
+    pub cluster_by: ClusterBy,
+
+    /// File to write the diagram to; standard output if not specified
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments of `aws collect`
+#[derive(clap::Args, Debug)]
+#[command(group(ArgGroup::new("role_accounts").args(["accounts", "organization"])))]
+#[command(group(ArgGroup::new("additional_regions").args(["regions", "all_regions"])))]
+pub struct CollectArgs {
+    /// AWS profile to use
+    #[arg(short, long, default_value = "default")]
+    pub profile: String,
+
+    /// Collect several profiles in one run (comma-separated)
+    #[arg(long, value_delimiter = ',', conflicts_with = "profile")]
+    pub profiles: Vec<String>,
+
+    /// Collect every profile of ~/.aws/config and ~/.aws/credentials
+    #[arg(long, conflicts_with_all = ["profile", "profiles"])]
+    pub all_profiles: bool,
+
+    /// Additional regions to collect from (comma-separated)
+    #[arg(short, long)]
+    pub regions: Option<String>,
+
+    /// Collect from every region enabled for the account (skips opt-in
+    /// regions that are not enabled)
+    #[arg(long, conflicts_with = "regions")]
+    pub all_regions: bool,
+
+    /// Services to collect from additional regions (comma-separated)
+    /// If not specified, all services will be collected from additional regions
+    #[arg(short = 's', long, requires = "additional_regions")]
+    pub region_services: Option<String>,
+
+    /// Create new timestamped files instead of overwriting existing ones
+    #[arg(short = 'n', long, default_value = "false")]
+    pub create_new_file: bool,
+
+    /// Maximum number of concurrent collectors (1-10)
+    #[arg(short = 'j', long, default_value = "5")]
+    pub concurrency: usize,
+
+    /// Adapt the number of in-flight calls per service: grow it while calls
+    /// succeed and halve it when the service throttles
+    #[arg(long)]
+    pub adaptive_concurrency: bool,
+
+    /// Do not share responses between identical AWS calls of the run
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Maximum attempts per AWS call when throttled or on transient errors
+    #[arg(long, default_value = "5", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_attempts: u32,
+
+    /// Seconds after which a single AWS CLI call is killed and recorded as
+    /// timed out
+    #[arg(long, value_name = "SECS", default_value = "300", value_parser = clap::value_parser!(u64).range(1..))]
+    pub call_timeout: u64,
+
+    /// Seconds after which a whole service collector is abandoned (no limit
+    /// if not specified)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub task_timeout: Option<u64>,
+
+    /// Maximum calls per second: RATE for every service, SERVICE=RATE or
+    /// SERVICE:OPERATION=RATE (repeatable, e.g. --rate-limit ec2=5)
+    #[arg(long, value_name = "[SERVICE[:OPERATION]=]RATE")]
+    pub rate_limit: Vec<String>,
+
+    /// JSON file of rate limits ({"default": 10, "limits": {"ec2": 5}});
+    /// --rate-limit values take precedence
+    #[arg(long, value_name = "FILE")]
+    pub rate_limit_config: Option<PathBuf>,
+
+    /// Backend used to call AWS APIs
+    #[arg(long, value_enum, default_value = "cli")]
+    pub backend: Backend,
+
+    /// Record every AWS CLI invocation as a cassette in this directory
+    #[arg(long, value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Replay AWS CLI invocations from cassettes in this directory (offline)
+    #[arg(long, value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+
+    /// Custom endpoint for every service (URL) or one CLI service
+    /// (SERVICE=URL), e.g. LocalStack; repeatable
+    #[arg(long, value_name = "[SERVICE=]URL")]
+    pub endpoint_url: Vec<String>,
+
+    /// IAM roles to assume from the profile, collecting each role's account
+    /// (comma-separated ARNs)
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["role_name", "organization"]
+    )]
+    pub role_arn: Vec<String>,
+
+    /// IAM role name to assume in every account given with --accounts or
+    /// --organization (default for --organization: OrganizationAccountAccessRole)
+    #[arg(long, requires = "role_accounts")]
+    pub role_name: Option<String>,
+
+    /// Account IDs to collect through --role-name (comma-separated)
+    #[arg(long, value_delimiter = ',', requires = "role_name")]
+    pub accounts: Vec<String>,
+
+    /// Collect every active account of the profile's AWS Organization
+    #[arg(long)]
+    pub organization: bool,
+
+    /// Only collect accounts in these organizational units, including
+    /// nested ones (comma-separated)
+    #[arg(long, value_delimiter = ',', requires = "organization")]
+    pub ou: Vec<String>,
+
+    /// Only collect accounts carrying this tag (KEY=VALUE, repeatable)
+    #[arg(long, value_parser = parse_tag, requires = "organization")]
+    pub account_tag: Vec<(String, String)>,
+
+    /// Session duration of assumed roles in seconds (900-43200; default:
+    /// the role's own). Sessions are renewed before they expire either way
+    #[arg(long, value_parser = clap::value_parser!(u64).range(900..=43200))]
+    pub duration_seconds: Option<u64>,
+
+    /// Which failures make the run exit with the partial-failure code (2)
+    #[arg(long, value_enum, default_value = "any")]
+    pub fail_on: FailOn,
+}
+
+/// Backend used to call AWS APIs
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// Shell out to the `aws` CLI for every call
+    Cli,
+    /// Sign requests with SigV4 and call JSON-protocol APIs over HTTP directly,
+    /// falling back to the `aws` CLI for other services
+    Http,
+}
+
+/// Format of an exported resource graph
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+    /// GraphML
+    Graphml,
+}
+
+/// Clusters of an exported resource graph
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClusterBy {
+    /// No clusters
+    None,
+    /// One cluster per VPC
+    Vpc,
+    /// One cluster per VPC, with a nested cluster per subnet
+    Subnet,
+}
+
+/// Failures that turn a completed collection into a partial failure
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailOn {
+    /// Any failed AWS call
+    Any,
+    /// A service that could not be collected in a region at all
+    ServiceError,
+    /// Any AWS call, service or role rejected for missing permissions
+    AccessDenied,
+    /// Never; only fatal errors fail the run
+    None,
+}
+
+/// Parse a `KEY=VALUE` tag filter
+fn parse_tag(tag: &str) -> Result<(String, String), String> {
+    match tag.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{}'", tag)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(["cloud_collector", "aws", "collect"].iter().chain(args))
+    }
+
+    #[test]
+    fn test_role_name_requires_accounts() {
+        assert!(parse(&["--role-name", "Audit"]).is_err());
+        assert!(parse(&["--role-name", "Audit", "--accounts", "111111111111"]).is_ok());
+        assert!(parse(&["--role-name", "Audit", "--organization"]).is_ok());
+    }
 }
@@ -1,11 +1,15 @@
 use anyhow::{Context, Result};
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::sync::Semaphore;
 
 use crate::aws;
-use crate::models::Metadata;
+use crate::aws::error::{AwsError, AwsErrorKind};
+use crate::cli::{Backend, ClusterBy, ExportGraphArgs, FailOn, GraphFormat};
+use crate::models::{CollectionFailure, Metadata, ResourceCollection};
 use crate::output;
 use crate::ui;
 
@@ -55,15 +59,256 @@ pub fn list_services() {
     println!("  cloud_collector aws collect --regions us-east-1 --region-services acm,cloudfront");
 }
 
-pub async fn collect_resources(
-    aws_profile: &str,
-    additional_regions: Option<&str>,
-    region_services: Option<&str>,
-    create_new_file: bool,
-    concurrency: usize,
+/// Options for the `aws collect` command
+pub struct CollectOptions {
+    pub profile: String,
+    /// Profiles to collect instead of `profile`
+    pub profiles: Vec<String>,
+    /// Collect every profile of the shared config and credentials files
+    pub all_profiles: bool,
+    pub regions: Option<String>,
+    /// Collect every region enabled for the account
+    pub all_regions: bool,
+    pub region_services: Option<String>,
+    pub create_new_file: bool,
+    pub concurrency: usize,
+    /// Adapt in-flight calls per service to throttling (AIMD)
+    pub adaptive_concurrency: bool,
+    /// Disable the run-scoped response cache
+    pub no_cache: bool,
+    pub max_attempts: u32,
+    pub backend: Backend,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    /// Seconds after which a single AWS CLI call is killed
+    pub call_timeout: u64,
+    /// Seconds after which a whole service collector is abandoned
+    pub task_timeout: Option<u64>,
+    /// `--rate-limit` values, applied over `rate_limit_config`
+    pub rate_limits: Vec<String>,
+    pub rate_limit_config: Option<PathBuf>,
+    /// `--endpoint-url` values: `URL` or `SERVICE=URL`
+    pub endpoint_urls: Vec<String>,
+    /// Roles to assume, one collected account each
+    pub role_arns: Vec<String>,
+    /// Role name assumed in every account of `accounts`
+    pub role_name: Option<String>,
+    pub accounts: Vec<String>,
+    /// Collect the organization's accounts matching the filter
+    pub organization: Option<aws::organizations::OrganizationFilter>,
+    /// `--duration-seconds` of assumed-role sessions
+    pub role_duration: Option<u64>,
+}
+
+/// Print the IAM policy allowing exactly the calls made by the given services
+///
+/// `assume_role` and `organization` add the calls of multi-account runs, made
+/// with the profile's own credentials.
+pub fn required_permissions(
+    services: Option<&str>,
+    assume_role: bool,
+    organization: bool,
 ) -> Result<()> {
-    println!("🚀 AWS Resource Lister");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    let service_types = match services {
+        None => aws::collectors::ServiceType::all(),
+        Some(list) => list
+            .split(',')
+            .map(|name| {
+                aws::collectors::ServiceType::from_str(name.trim()).with_context(|| {
+                    format!(
+                        "Unknown service '{}'. Run `cloud_collector aws list-services` to see the supported services",
+                        name.trim()
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?,
+    };
+
+    let mut operations = aws::permissions::base_operations();
+    if organization {
+        operations.extend(aws::permissions::organization_operations());
+    } else if assume_role {
+        operations.extend(aws::permissions::assume_role_operations());
+    }
+    for service_type in service_types {
+        operations.extend(aws::collectors::get_collector(service_type).operations());
+    }
+
+    let policy = aws::permissions::policy_document(&operations);
+    println!("{}", serde_json::to_string_pretty(&policy)?);
+
+    Ok(())
+}
+
+/// Render the relationship graph of a collected snapshot
+pub fn export_graph(args: &ExportGraphArgs) -> Result<()> {
+    use aws::graph_export::{Clustering, Diagram, GraphFilter};
+
+    let snapshot = args.output_dir.join(&args.profile);
+    let graph = aws::graph::Graph::load(&snapshot)?;
+
+    let filter = GraphFilter {
+        vpcs: args.vpc.clone(),
+        regions: args.region.clone(),
+        tags: args.tag.clone(),
+        services: args.service.clone(),
+    };
+    let clustering = match args.cluster_by {
+        ClusterBy::None => Clustering::None,
+        ClusterBy::Vpc => Clustering::Vpc,
+        ClusterBy::Subnet => Clustering::Subnet,
+    };
+    let diagram = Diagram::new(&graph, &filter, clustering);
+
+    let rendered = match args.format {
+        GraphFormat::Dot => diagram.to_dot(),
+        GraphFormat::Mermaid => diagram.to_mermaid(),
+        GraphFormat::Graphml => diagram.to_graphml(),
+    };
+
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, rendered)
+                .context(format!("Failed to write {}", path.display()))?;
+            eprintln!("✓ Wrote: {}", path.display());
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+/// Number of slowest AWS calls listed after a collection
+const SLOWEST_CALLS: usize = 5;
+
+/// Exit code of a run without failures counted by `--fail-on`
+pub const EXIT_SUCCESS: i32 = 0;
+
+/// Exit code of a run that collected nothing: it could not start, or every
+/// service failed
+pub const EXIT_FATAL_FAILURE: i32 = 1;
+
+/// Exit code of a run that completed with failures matching `--fail-on`
+pub const EXIT_PARTIAL_FAILURE: i32 = 2;
+
+/// Failures of a completed collection run
+#[derive(Debug, Default)]
+pub struct CollectionSummary {
+    /// Services that could not be collected at all, labelled
+    /// `service (region)`, and roles or profiles that could not be used
+    pub failed_services: Vec<CollectionFailure>,
+    /// Number of services collected in a region, at least partially
+    pub collected_services: usize,
+    /// Error class of every failed AWS call
+    pub failed_calls: Vec<AwsErrorKind>,
+    /// Calls the endpoint does not implement (AWS emulators); these degrade
+    /// the output but do not fail the run
+    pub unsupported_calls: usize,
+}
+
+impl CollectionSummary {
+    fn new(collections: &[ResourceCollection], failed_tasks: Vec<CollectionFailure>) -> Self {
+        let mut failed_services = failed_tasks;
+
+        // A service counts as failed in a region when none of its resource
+        // types could be collected; it fails with the class of its first error
+        let mut by_service: HashMap<(&str, &str), Option<AwsErrorKind>> = HashMap::new();
+        for collection in collections {
+            let failure = collection
+                .errors
+                .iter()
+                .map(|e| e.error_class)
+                .find(|class| *class != AwsErrorKind::Unsupported)
+                .filter(|_| collection.is_failed());
+            by_service
+                .entry((&collection.service, &collection.region))
+                .and_modify(|failed| *failed = failure.and(*failed))
+                .or_insert(failure);
+        }
+        let collected_services = by_service.values().filter(|f| f.is_none()).count();
+        let mut fully_failed: Vec<CollectionFailure> = by_service
+            .into_iter()
+            .filter_map(|((service, region), failure)| {
+                failure
+                    .map(|kind| CollectionFailure::new(format!("{} ({})", service, region), kind))
+            })
+            .collect();
+        fully_failed.sort_by(|a, b| a.label.cmp(&b.label));
+        failed_services.extend(fully_failed);
+
+        let (unsupported, failed_calls): (Vec<_>, Vec<_>) = collections
+            .iter()
+            .flat_map(|c| c.errors.iter().map(|e| e.error_class))
+            .partition(|class| *class == AwsErrorKind::Unsupported);
+
+        Self {
+            failed_services,
+            collected_services,
+            failed_calls,
+            unsupported_calls: unsupported.len(),
+        }
+    }
+
+    /// Process exit code for the run under the given policy
+    ///
+    /// A run in which every service failed is a fatal failure whatever the
+    /// policy.
+    pub fn exit_code(&self, fail_on: FailOn) -> i32 {
+        if self.collected_services == 0 && !self.failed_services.is_empty() {
+            return EXIT_FATAL_FAILURE;
+        }
+
+        let failed = match fail_on {
+            FailOn::Any => !self.failed_services.is_empty() || !self.failed_calls.is_empty(),
+            FailOn::ServiceError => !self.failed_services.is_empty(),
+            FailOn::AccessDenied => {
+                self.failed_calls.contains(&AwsErrorKind::AccessDenied)
+                    || self
+                        .failed_services
+                        .iter()
+                        .any(|failure| failure.kind == AwsErrorKind::AccessDenied)
+            }
+            FailOn::None => false,
+        };
+
+        if failed {
+            EXIT_PARTIAL_FAILURE
+        } else {
+            EXIT_SUCCESS
+        }
+    }
+}
+
+/// Collection marking a service whose collector exceeded the task timeout
+fn timed_out_collection(service: &str, region: &str, timeout: Duration) -> ResourceCollection {
+    let error = AwsError::timed_out(&format!("{} collector", service), timeout);
+    ResourceCollection::from_result(
+        service,
+        region,
+        "all",
+        &[service],
+        Err(error.into()),
+        &chrono::Utc::now().to_rfc3339(),
+    )
+}
+
+/// Build the command executor for a run (live, recording or replaying)
+async fn build_executor(
+    options: &CollectOptions,
+) -> Result<Arc<dyn aws::executor::CommandExecutor>> {
+    if let Some(dir) = &options.replay {
+        let replay =
+            aws::cassette::ReplayExecutor::load(dir).context("Failed to load cassettes")?;
+        if replay.is_empty() {
+            anyhow::bail!("No cassettes found in {}", dir.display());
+        }
+        println!(
+            "📼 Replaying {} recorded AWS CLI calls from {}\n",
+            replay.len(),
+            dir.display()
+        );
+        return Ok(Arc::new(replay));
+    }
 
     // Check if AWS CLI is available
     println!("🔍 Checking AWS CLI...");
@@ -72,11 +317,51 @@ pub async fn collect_resources(
         .context("AWS CLI check failed")?;
     println!("✓ AWS CLI is available\n");
 
-    // Initialize AWS CLI
-    let cli = aws::cli::AwsCli::new(aws_profile.to_string());
+    let subprocess: Arc<dyn aws::executor::CommandExecutor> =
+        Arc::new(aws::executor::SubprocessExecutor);
+
+    let executor: Arc<dyn aws::executor::CommandExecutor> = match options.backend {
+        Backend::Cli => subprocess,
+        Backend::Http => {
+            println!("⚡ Using native HTTP backend (aws CLI fallback for non-JSON services)\n");
+            Arc::new(aws::http::HttpExecutor::new(subprocess))
+        }
+    };
+
+    match &options.record {
+        Some(dir) => {
+            println!("⏺️  Recording AWS CLI calls to {}\n", dir.display());
+            Ok(Arc::new(aws::cassette::RecordingExecutor::new(
+                executor, dir,
+            )?))
+        }
+        None => Ok(executor),
+    }
+}
+
+/// One account collected through one profile
+struct AccountRun {
+    profile: String,
+    target: aws::targets::CollectionTarget,
+    default_region: String,
+    regions: Vec<String>,
+    /// Prefix of the run's progress and failure labels when a run collects
+    /// several profiles or accounts, e.g. `prod/111111111111/`
+    label_prefix: String,
+}
 
+/// Validate a profile and resolve the accounts and regions collected with it
+///
+/// Returns the runs of the profile and the roles that could not be assumed.
+async fn prepare_profile(
+    cli: &aws::cli::AwsCli,
+    options: &CollectOptions,
+) -> Result<(Vec<AccountRun>, Vec<CollectionFailure>)> {
     // Validate credentials before proceeding
-    println!("🔐 Validating AWS credentials...");
+    println!(
+        "🔐 Validating AWS credentials for profile {}...",
+        cli.profile()
+    );
     cli.validate_credentials()
         .await
         .context("Credential validation failed")?;
@@ -91,10 +376,28 @@ pub async fn collect_resources(
         .await
         .context("Failed to get default region")?;
 
+    let catalog = aws::regions::RegionCatalog::load(cli).await?;
+
     let mut regions = vec![default_region.clone()];
 
-    // Add additional regions if specified
-    if let Some(region_str) = additional_regions {
+    if options.all_regions {
+        // Every region enabled for the account
+        regions.extend(
+            catalog
+                .enabled()
+                .into_iter()
+                .filter(|r| r != &default_region),
+        );
+        let opted_out = catalog.opted_out();
+        if !opted_out.is_empty() {
+            println!(
+                "ℹ️  Skipping {} regions not enabled for the account (opt-in required): {}",
+                opted_out.len(),
+                opted_out.join(", ")
+            );
+        }
+    } else if let Some(region_str) = options.regions.as_deref() {
+        // Add additional regions if specified
         let additional: Vec<String> = region_str
             .split(',')
             .map(|s| s.trim().to_string())
@@ -104,19 +407,188 @@ pub async fn collect_resources(
     }
 
     // Validate all regions before starting collection
-    aws::regions::validate_regions(&cli, &regions)
-        .await
-        .context("Region validation failed")?;
+    aws::regions::validate_regions(&catalog, &regions).context("Region validation failed")?;
+
+    // Resolve the accounts to collect, assuming roles if requested
+    let mut role_arns = options.role_arns.clone();
+    // `--role-name` without `--accounts` is only used by `--organization`
+    if let Some(role_name) = options
+        .role_name
+        .as_ref()
+        .filter(|_| !options.accounts.is_empty())
+    {
+        let partition = cli
+            .caller_arn()
+            .await
+            .context("Failed to determine the caller's partition")?
+            .partition;
+        role_arns.extend(
+            options
+                .accounts
+                .iter()
+                .map(|account| aws::targets::role_arn_for(&partition, account, role_name)),
+        );
+    }
+    let (targets, failures) = match &options.organization {
+        Some(filter) => {
+            println!("🏢 Discovering organization accounts...");
+            let role_name = options
+                .role_name
+                .as_deref()
+                .unwrap_or(aws::organizations::DEFAULT_ROLE_NAME);
+            aws::organizations::resolve_targets(cli, filter, role_name, &default_region)
+                .await
+                .context("Failed to resolve organization accounts")?
+        }
+        None => {
+            if !role_arns.is_empty() {
+                println!("🎭 Assuming {} roles...", role_arns.len());
+            }
+            aws::targets::resolve_targets(cli, &role_arns, &default_region)
+                .await
+                .context("Failed to resolve accounts")?
+        }
+    };
+
+    let runs = targets
+        .into_iter()
+        .map(|target| AccountRun {
+            profile: cli.profile().to_string(),
+            target,
+            default_region: default_region.clone(),
+            regions: regions.clone(),
+            label_prefix: String::new(),
+        })
+        .collect();
+
+    Ok((runs, failures))
+}
+
+pub async fn collect_resources(options: CollectOptions) -> Result<CollectionSummary> {
+    println!("🚀 AWS Resource Lister");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    let executor = build_executor(&options).await?;
+
+    let region_services = options.region_services.as_deref();
+    let create_new_file = options.create_new_file;
+    let task_timeout = options.task_timeout.map(Duration::from_secs);
+    let concurrency = options.concurrency;
+
+    // Profiles to collect
+    let profiles = if options.all_profiles {
+        aws::profiles::discover_profiles().context("Failed to discover AWS profiles")?
+    } else if !options.profiles.is_empty() {
+        options.profiles.clone()
+    } else {
+        vec![options.profile.clone()]
+    };
+    let multi_profile = profiles.len() > 1;
+    // `--role-name` only names the role; the accounts come with it
+    let multi_account = !options.role_arns.is_empty()
+        || !options.accounts.is_empty()
+        || options.organization.is_some();
+
+    let endpoints = aws::endpoints::Endpoints::parse(&options.endpoint_urls)?;
+    if !endpoints.is_empty() {
+        println!(
+            "🧪 Using custom endpoints: {}\n",
+            options.endpoint_urls.join(", ")
+        );
+    }
 
     // Configuration
     let output_directory = "./output".to_string();
 
+    // Audit log of every AWS API call, next to the output files
+    let audit_file = if create_new_file {
+        format!("audit_{}.jsonl", chrono::Utc::now().format("%Y%m%d_%H%M%S"))
+    } else {
+        "audit.jsonl".to_string()
+    };
+    let audit = Arc::new(aws::audit::AuditLog::create(
+        &std::path::Path::new(&output_directory).join(audit_file),
+    )?);
+
+    let mut rate_limits = match &options.rate_limit_config {
+        Some(path) => aws::rate_limit::RateLimits::load(path)?,
+        None => aws::rate_limit::RateLimits::default(),
+    };
+    for spec in &options.rate_limits {
+        rate_limits.apply(spec)?;
+    }
+    if !rate_limits.is_empty() {
+        println!("🚦 Rate limiting AWS API calls per service\n");
+    }
+
+    let adaptive = Arc::new(aws::adaptive::AdaptiveConcurrency::default());
+
+    // Initialize AWS CLI
+    let mut cli = aws::cli::AwsCli::with_executor(profiles[0].clone(), executor)
+        .with_retry_policy(aws::retry::RetryPolicy::with_max_attempts(
+            options.max_attempts,
+        ))
+        .with_endpoints(endpoints)
+        .with_audit_log(Arc::clone(&audit))
+        .with_call_timeout(Duration::from_secs(options.call_timeout))
+        .with_rate_limits(rate_limits);
+    if options.adaptive_concurrency {
+        cli = cli.with_adaptive_concurrency(Arc::clone(&adaptive));
+    }
+    if let Some(seconds) = options.role_duration {
+        cli = cli.with_role_duration(seconds);
+    }
+    let cache = Arc::new(aws::cache::ResponseCache::default());
+    if !options.no_cache {
+        cli = cli.with_response_cache(Arc::clone(&cache));
+    }
+
+    // Validate every profile up front; with several profiles, a profile that
+    // cannot be used is reported instead of aborting the run
+    let mut runs = Vec::new();
+    let mut setup_failures = Vec::new();
+    for profile in &profiles {
+        let profile_cli = cli.for_profile(profile);
+        match prepare_profile(&profile_cli, &options).await {
+            Ok((profile_runs, failures)) => {
+                runs.extend(profile_runs);
+                setup_failures.extend(failures);
+            }
+            Err(e) if multi_profile => {
+                eprintln!("⚠️  Skipping profile {}: {:#}\n", profile, e);
+                setup_failures.push(CollectionFailure::new(
+                    format!("profile {}", profile),
+                    AwsErrorKind::of(&e),
+                ));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    if runs.is_empty() {
+        anyhow::bail!("None of the {} profiles could be used", profiles.len());
+    }
+    for run in &mut runs {
+        let mut prefix = Vec::new();
+        if multi_profile {
+            prefix.push(run.profile.clone());
+        }
+        if multi_account {
+            prefix.push(run.target.account_id.clone());
+        }
+        run.label_prefix = prefix.iter().map(|part| format!("{}/", part)).collect();
+    }
+
     // All supported services are enabled by default
     let enabled_services = aws::collectors::get_all_services();
 
     println!("✓ Configuration loaded");
-    println!("  Profile: {}", aws_profile);
-    println!("  Regions: {}", regions.join(", "));
+    for run in &runs {
+        println!(
+            "  Profile: {} (account {})",
+            run.profile, run.target.account_id
+        );
+        println!("    Regions: {}", run.regions.join(", "));
+    }
     println!("  Concurrency: {} collectors", concurrency);
     println!("  Services ({}): ", enabled_services.len());
     for (i, service) in enabled_services.iter().enumerate() {
@@ -133,7 +605,11 @@ pub async fn collect_resources(
         concurrency
     );
 
-    let all_collections = Arc::new(Mutex::new(Vec::new()));
+    // Collections of each run, in the order of `runs`
+    let all_collections = Arc::new(Mutex::new(vec![Vec::new(); runs.len()]));
+    // Collector durations of each run, for the run manifest
+    let all_durations = Arc::new(Mutex::new(vec![output::Durations::new(); runs.len()]));
+    let failed_tasks = Arc::new(Mutex::new(setup_failures));
     let processed_global_services = Arc::new(Mutex::new(HashSet::new()));
     let semaphore = Arc::new(Semaphore::new(concurrency));
 
@@ -172,103 +648,151 @@ pub async fn collect_resources(
         );
     }
 
-    for service_name in enabled_services.clone() {
-        let service_type = match aws::collectors::ServiceType::from_str(&service_name) {
-            Some(s) => s,
-            None => {
-                eprintln!("⚠ Warning: Unknown service '{}'", service_name);
-                continue;
-            }
-        };
+    for (run_index, run) in runs.iter().enumerate() {
+        for service_name in enabled_services.clone() {
+            let service_type = match aws::collectors::ServiceType::from_str(&service_name) {
+                Some(s) => s,
+                None => {
+                    eprintln!("⚠ Warning: Unknown service '{}'", service_name);
+                    continue;
+                }
+            };
 
-        for region in regions.clone() {
-            // Skip global services in non-default regions
-            if service_type.is_global() && region != default_region {
-                continue;
-            }
+            for region in run.regions.clone() {
+                // Skip global services in non-default regions
+                if service_type.is_global() && region != run.default_region {
+                    continue;
+                }
 
-            // For additional regions (not default), check if service is in region_services list
-            if region != default_region {
-                if let Some(ref allowed_services) = region_services_set {
-                    if !allowed_services.contains(&service_name) {
-                        continue; // Skip this service for this additional region
+                // For additional regions (not default), check if service is in region_services list
+                if region != run.default_region {
+                    if let Some(ref allowed_services) = region_services_set {
+                        if !allowed_services.contains(&service_name) {
+                            continue; // Skip this service for this additional region
+                        }
                     }
                 }
-            }
 
-            // Check if this is a global service and we've already processed it
-            if service_type.is_global() {
-                let mut processed = processed_global_services.lock().await;
-                if processed.contains(&service_name) {
-                    continue;
+                // Check if this is a global service and we've already processed it
+                if service_type.is_global() {
+                    let mut processed = processed_global_services.lock().await;
+                    if !processed.insert((run_index, service_name.clone())) {
+                        continue;
+                    }
                 }
-                processed.insert(service_name.clone());
-            }
 
-            // Increment total tasks
-            {
-                let mut total = total_tasks.lock().await;
-                *total += 1;
-            }
+                // Increment total tasks
+                {
+                    let mut total = total_tasks.lock().await;
+                    *total += 1;
+                }
 
-            // Create progress bar for this task
-            let pb = ui::create_service_progress_bar(&multi, &service_name, &region);
+                // Create progress bar for this task
+                let display_name = format!("{}{}", run.label_prefix, service_name);
+                let pb = ui::create_service_progress_bar(&multi, &display_name, &region);
 
-            let cli = cli.clone();
-            let region = region.clone();
-            let all_collections = Arc::clone(&all_collections);
-            let semaphore = Arc::clone(&semaphore);
-            let service_name = service_name.clone();
-            let completed_tasks = Arc::clone(&completed_tasks);
+                let label = format!("{} ({})", display_name, region);
+                let cli = run.target.cli.for_collector(&service_name);
+                let region = region.clone();
+                let all_collections = Arc::clone(&all_collections);
+                let all_durations = Arc::clone(&all_durations);
+                let failed_tasks = Arc::clone(&failed_tasks);
+                let semaphore = Arc::clone(&semaphore);
+                let task_label = label.clone();
+                let service_name = service_name.clone();
+                let completed_tasks = Arc::clone(&completed_tasks);
 
-            let task = tokio::spawn(async move {
-                // Acquire semaphore permit
-                let _permit = semaphore.acquire().await.unwrap();
+                let task = tokio::spawn(async move {
+                    // Acquire semaphore permit
+                    let _permit = semaphore.acquire().await.unwrap();
 
-                // Update to running state
-                ui::set_progress_running(&pb);
+                    // Update to running state
+                    ui::set_progress_running(&pb);
 
-                let start_time = std::time::Instant::now();
-                let collector = aws::collectors::get_collector(service_type);
+                    let start_time = std::time::Instant::now();
+                    let collector = aws::collectors::get_collector(service_type);
 
-                let success = match collector.collect(&cli, &region).await {
-                    Ok(collections) => {
-                        let mut all = all_collections.lock().await;
-                        all.extend(collections);
-                        true
-                    }
-                    Err(e) => {
-                        eprintln!(
-                            "\n⚠️  Error collecting {} in {}: {}",
-                            service_name, region, e
-                        );
-                        false
-                    }
-                };
+                    let result = match task_timeout {
+                        Some(timeout) => {
+                            match tokio::time::timeout(timeout, collector.collect(&cli, &region))
+                                .await
+                            {
+                                Ok(result) => result,
+                                // The collector's calls are dropped, which kills
+                                // their processes; record the service as timed out
+                                Err(_) => Ok(vec![timed_out_collection(
+                                    &service_name,
+                                    if service_type.is_global() {
+                                        "global"
+                                    } else {
+                                        &region
+                                    },
+                                    timeout,
+                                )]),
+                            }
+                        }
+                        None => collector.collect(&cli, &region).await,
+                    };
 
-                let elapsed = start_time.elapsed().as_secs_f64();
+                    let elapsed = start_time.elapsed().as_secs_f64();
 
-                // Update progress bar based on result
-                if success {
-                    ui::set_progress_completed(&pb, elapsed);
-                } else {
-                    ui::set_progress_error(&pb);
-                }
+                    let success = match result {
+                        Ok(collections) => {
+                            // Key the duration like the output file it describes;
+                            // a service collected from several regions into one
+                            // `global` file accumulates their durations
+                            let files: HashSet<(String, String)> = collections
+                                .iter()
+                                .map(|c| (c.service.clone(), c.region.clone()))
+                                .collect();
+                            let mut durations = all_durations.lock().await;
+                            for file in files {
+                                *durations[run_index].entry(file).or_default() += elapsed;
+                            }
+                            drop(durations);
 
-                // Increment completed tasks
-                {
-                    let mut completed = completed_tasks.lock().await;
-                    *completed += 1;
-                }
-            });
+                            let mut all = all_collections.lock().await;
+                            all[run_index].extend(collections);
+                            true
+                        }
+                        Err(e) => {
+                            eprintln!("\n⚠️  Error collecting {}: {}", task_label, e);
+                            failed_tasks
+                                .lock()
+                                .await
+                                .push(CollectionFailure::new(task_label, AwsErrorKind::of(&e)));
+                            false
+                        }
+                    };
+
+                    // Update progress bar based on result
+                    if success {
+                        ui::set_progress_completed(&pb, elapsed);
+                    } else {
+                        ui::set_progress_error(&pb);
+                    }
 
-            tasks.push(task);
+                    // Increment completed tasks
+                    {
+                        let mut completed = completed_tasks.lock().await;
+                        *completed += 1;
+                    }
+                });
+
+                tasks.push((label, task));
+            }
         }
     }
 
-    // Wait for all tasks to complete
-    for task in tasks {
-        let _ = task.await;
+    // Wait for all tasks to complete; a panicked task counts as a failed service
+    for (label, task) in tasks {
+        if let Err(e) = task.await {
+            eprintln!("\n⚠️  Collector for {} aborted: {}", label, e);
+            failed_tasks
+                .lock()
+                .await
+                .push(CollectionFailure::new(label, AwsErrorKind::Unknown));
+        }
     }
 
     // Wait for summary task to finish
@@ -279,28 +803,267 @@ pub async fn collect_resources(
 
     // Unwrap Arc to get collections
     let all_collections = Arc::try_unwrap(all_collections).unwrap().into_inner();
+    let all_durations = Arc::try_unwrap(all_durations).unwrap().into_inner();
+    let failed_tasks = Arc::try_unwrap(failed_tasks).unwrap().into_inner();
+    let mut summary = CollectionSummary::new(&[], failed_tasks);
+    for (run, collections) in runs.iter().zip(&all_collections) {
+        let run_summary = CollectionSummary::new(collections, Vec::new());
+        summary.failed_calls.extend(run_summary.failed_calls);
+        summary.unsupported_calls += run_summary.unsupported_calls;
+        summary.collected_services += run_summary.collected_services;
+        summary
+            .failed_services
+            .extend(
+                run_summary
+                    .failed_services
+                    .into_iter()
+                    .map(|failure| CollectionFailure {
+                        label: format!("{}{}", run.label_prefix, failure.label),
+                        ..failure
+                    }),
+            );
+    }
 
     println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("📊 Total collections: {}", all_collections.len());
+    println!(
+        "📊 Total collections: {}",
+        all_collections.iter().map(Vec::len).sum::<usize>()
+    );
+
+    let retries = cli.retry_summary();
+    if retries.retries > 0 || retries.exhausted > 0 {
+        println!(
+            "🔁 Retries: {} ({} throttled attempts), {} calls failed after {} attempts",
+            retries.retries, retries.throttled, retries.exhausted, options.max_attempts
+        );
+    }
+
+    if cache.hits() > 0 {
+        println!(
+            "♻️  {} duplicate AWS calls served from the run cache",
+            cache.hits()
+        );
+    }
+
+    let limits = adaptive.summary();
+    if !limits.is_empty() {
+        let chosen: Vec<String> = limits
+            .iter()
+            .map(|l| {
+                if l.decreases > 0 {
+                    format!("{} {} (throttled {}x)", l.service, l.limit, l.decreases)
+                } else {
+                    format!("{} {}", l.service, l.limit)
+                }
+            })
+            .collect();
+        println!(
+            "⚙️  Adaptive concurrency (calls in flight per service): {}",
+            chosen.join(", ")
+        );
+    }
+
+    audit.flush()?;
+    println!(
+        "📝 Audit log: {} AWS calls recorded in {}",
+        audit.len(),
+        audit.path().display()
+    );
+    let slowest = audit.slowest(SLOWEST_CALLS);
+    if !slowest.is_empty() {
+        println!("🐢 Slowest calls:");
+        for call in slowest {
+            println!(
+                "   {:>8.1}s  {} {} ({}{})",
+                call.duration_ms as f64 / 1000.0,
+                call.service,
+                call.operation,
+                call.collector.as_deref().unwrap_or("setup"),
+                call.region
+                    .map(|region| format!(", {}", region))
+                    .unwrap_or_default()
+            );
+        }
+    }
+
+    if !summary.failed_services.is_empty() {
+        let labels: Vec<&str> = summary
+            .failed_services
+            .iter()
+            .map(|failure| failure.label.as_str())
+            .collect();
+        println!("❌ Failed services: {}", labels.join(", "));
+    }
+
+    if summary.unsupported_calls > 0 {
+        println!(
+            "ℹ️  {} operations are not implemented by the endpoint and were skipped",
+            summary.unsupported_calls
+        );
+    }
+
+    let mut failures_by_class = HashMap::new();
+    for class in &summary.failed_calls {
+        *failures_by_class.entry(*class).or_insert(0) += 1;
+    }
+    if !failures_by_class.is_empty() {
+        let mut classes: Vec<_> = failures_by_class.into_iter().collect();
+        classes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let total: usize = classes.iter().map(|(_, count)| count).sum();
+        let breakdown: Vec<String> = classes
+            .iter()
+            .map(|(class, count)| format!("{}: {}", class, count))
+            .collect();
+        println!(
+            "⚠ {} commands failed ({}), see \"errors\" in the output files",
+            total,
+            breakdown.join(", ")
+        );
+    }
 
     // Write output
     println!("\n💾 Writing output...");
-    let metadata = Metadata {
-        generated_at: chrono::Utc::now().to_rfc3339(),
-        aws_profile: aws_profile.to_string(),
-        regions: regions.clone(),
-        services: enabled_services,
-    };
+    let arguments: Vec<String> = std::env::args().collect();
+    for ((run, collections), durations) in runs.into_iter().zip(all_collections).zip(all_durations)
+    {
+        let metadata = Metadata {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            arguments: arguments.clone(),
+            aws_profile: run.profile,
+            role_arn: run.target.cli.role_arn().map(str::to_string),
+            account_id: run.target.account_id,
+            partition: run.target.partition,
+            regions: run.regions,
+            services: enabled_services.clone(),
+        };
 
-    output::write_output(
-        all_collections,
-        &output_directory,
-        create_new_file,
-        metadata,
-        aws_profile,
-    )?;
+        output::write_output(
+            collections,
+            &output_directory,
+            create_new_file,
+            metadata,
+            &durations,
+            multi_account,
+        )?;
+    }
 
     println!("\n✅ Done!");
 
-    Ok(())
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CollectionError;
+    use serde_json::{json, Value};
+
+    fn collection(service: &str, resource_type: &str, error: Option<&str>) -> ResourceCollection {
+        let collection =
+            ResourceCollection::new(service, "us-east-1", resource_type, json!({}), "now");
+        match error {
+            None => collection,
+            Some(stderr) => {
+                let error =
+                    crate::aws::error::AwsError::from_output(&crate::aws::executor::CliOutput {
+                        exit_code: 254,
+                        stdout: String::new(),
+                        stderr: stderr.to_string(),
+                    });
+                ResourceCollection {
+                    resources: Value::Null,
+                    ..collection
+                }
+                .with_errors(
+                    CollectionError::from_failure(&[service], &error.into())
+                        .into_iter()
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn test_exit_code_follows_fail_on_policy() {
+        let clean = CollectionSummary::new(&[collection("ec2", "vpcs", None)], Vec::new());
+        for policy in [
+            FailOn::Any,
+            FailOn::ServiceError,
+            FailOn::AccessDenied,
+            FailOn::None,
+        ] {
+            assert_eq!(clean.exit_code(policy), 0);
+        }
+
+        // One resource type denied, the service itself still collected
+        let denied = CollectionSummary::new(
+            &[
+                collection("ec2", "vpcs", None),
+                collection("ec2", "snapshots", Some("An error occurred (AccessDenied)")),
+            ],
+            Vec::new(),
+        );
+        assert_eq!(denied.exit_code(FailOn::Any), EXIT_PARTIAL_FAILURE);
+        assert_eq!(denied.exit_code(FailOn::AccessDenied), EXIT_PARTIAL_FAILURE);
+        assert_eq!(denied.exit_code(FailOn::ServiceError), 0);
+        assert_eq!(denied.exit_code(FailOn::None), 0);
+
+        // Every service failed: nothing was collected
+        let nothing = CollectionSummary::new(
+            &[collection(
+                "sqs",
+                "queues",
+                Some("An error occurred (InternalError)"),
+            )],
+            Vec::new(),
+        );
+        for policy in [FailOn::Any, FailOn::None] {
+            assert_eq!(nothing.exit_code(policy), EXIT_FATAL_FAILURE);
+        }
+
+        // Every resource type of a service failed
+        let broken = CollectionSummary::new(
+            &[
+                collection("ec2", "vpcs", None),
+                collection("sqs", "queues", Some("An error occurred (InternalError)")),
+            ],
+            Vec::new(),
+        );
+        assert_eq!(
+            broken.failed_services,
+            vec![CollectionFailure::new(
+                "sqs (us-east-1)",
+                AwsErrorKind::ServiceUnavailable
+            )]
+        );
+        assert_eq!(broken.exit_code(FailOn::ServiceError), EXIT_PARTIAL_FAILURE);
+        assert_eq!(broken.exit_code(FailOn::AccessDenied), 0);
+
+        // A service, or role, that failed entirely for missing permissions
+        let denied_service = CollectionSummary::new(
+            &[collection("ec2", "vpcs", None)],
+            vec![CollectionFailure::new(
+                "lambda (us-east-1)",
+                AwsErrorKind::AccessDenied,
+            )],
+        );
+        assert!(denied_service.failed_calls.is_empty());
+        assert_eq!(
+            denied_service.exit_code(FailOn::AccessDenied),
+            EXIT_PARTIAL_FAILURE
+        );
+
+        // Operations an emulator does not implement do not fail the run
+        let emulated = CollectionSummary::new(
+            &[collection(
+                "wafv2",
+                "web_acls",
+                Some("An error occurred (NotImplemented)"),
+            )],
+            Vec::new(),
+        );
+        assert_eq!(emulated.unsupported_calls, 1);
+        assert_eq!(emulated.exit_code(FailOn::Any), 0);
+    }
 }
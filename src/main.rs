@@ -1,6 +1,7 @@
 mod aws;
 mod cli;
 mod commands;
+mod manifest;
 mod models;
 mod output;
 mod parallel;
@@ -13,7 +14,7 @@ mod utils;
 use anyhow::Result;
 use clap::Parser;
 
-use crate::cli::{AwsCommands, Cli, Provider};
+use crate::cli::{AwsCommands, Cli, CollectArgs, Provider};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -22,27 +23,101 @@ async fn main() -> Result<()> {
     match cli.provider {
         Provider::Aws { command } => {
             match command {
-                AwsCommands::Collect {
-                    profile,
-                    regions,
-                    region_services,
-                    create_new_file,
-                    concurrency,
-                } => {
+                AwsCommands::Collect(args) => {
+                    let CollectArgs {
+                        profile,
+                        profiles,
+                        all_profiles,
+                        regions,
+                        all_regions,
+                        region_services,
+                        create_new_file,
+                        concurrency,
+                        adaptive_concurrency,
+                        no_cache,
+                        max_attempts,
+                        call_timeout,
+                        task_timeout,
+                        rate_limit,
+                        rate_limit_config,
+                        backend,
+                        record,
+                        replay,
+                        endpoint_url,
+                        role_arn,
+                        role_name,
+                        accounts,
+                        organization,
+                        ou,
+                        account_tag,
+                        duration_seconds,
+                        fail_on,
+                    } = *args;
+
                     // Validate concurrency
                     let concurrency = concurrency.clamp(1, 10);
-                    commands::aws::collect_resources(
-                        &profile,
-                        regions.as_deref(),
-                        region_services.as_deref(),
+                    let summary = commands::aws::collect_resources(commands::aws::CollectOptions {
+                        profile,
+                        profiles,
+                        all_profiles,
+                        regions,
+                        all_regions,
+                        region_services,
                         create_new_file,
                         concurrency,
-                    )
-                    .await?;
+                        adaptive_concurrency,
+                        no_cache,
+                        max_attempts,
+                        call_timeout,
+                        task_timeout,
+                        rate_limits: rate_limit,
+                        rate_limit_config,
+                        backend,
+                        record,
+                        replay,
+                        endpoint_urls: endpoint_url,
+                        role_arns: role_arn,
+                        role_name,
+                        accounts,
+                        organization: organization.then_some(
+                            aws::organizations::OrganizationFilter {
+                                ous: ou,
+                                tags: account_tag,
+                            },
+                        ),
+                        role_duration: duration_seconds,
+                    })
+                    .await;
+
+                    // Fatal errors exit like anyhow's, under the documented code
+                    let exit_code = match summary {
+                        Ok(summary) => summary.exit_code(fail_on),
+                        Err(e) => {
+                            eprintln!("Error: {:?}", e);
+                            commands::aws::EXIT_FATAL_FAILURE
+                        }
+                    };
+                    if exit_code != commands::aws::EXIT_SUCCESS {
+                        std::process::exit(exit_code);
+                    }
                 }
                 AwsCommands::ListServices => {
                     commands::aws::list_services();
                 }
+                AwsCommands::RequiredPermissions {
+                    services,
+                    assume_role,
+                    organization,
+                } => {
+                    commands::aws::required_permissions(
+                        services.as_deref(),
+                        assume_role,
+                        organization,
+                    )?;
+                }
+                AwsCommands::ExportGraph(args) => {
+                    commands::aws::export_graph(&args)?;
+                }
             }
         }
         Provider::Gcp => {
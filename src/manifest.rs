@@ -0,0 +1,73 @@
+// Run manifest
+//
+// `manifest.json` describes what one run wrote for one account: the run
+// metadata, the files of every service and region with their resource counts,
+// durations and errors, and a SHA-256 checksum of each file, so downstream
+// jobs can validate and index a collection without opening every file.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::models::Metadata;
+
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    #[serde(flatten)]
+    pub metadata: Metadata,
+    pub collections: Vec<CollectionEntry>,
+    pub graph: FileEntry,
+}
+
+/// The files written for one service in one region
+#[derive(Debug, Serialize)]
+pub struct CollectionEntry {
+    pub service: String,
+    pub region: String,
+    /// Wall-clock time of the service's collector, if it was recorded
+    pub duration_secs: Option<f64>,
+    pub resource_types: usize,
+    /// Number of normalized resources
+    pub resources: usize,
+    pub errors: usize,
+    /// Raw `_all` file
+    pub raw: FileEntry,
+    /// Normalized `_resources` file
+    pub normalized: FileEntry,
+}
+
+/// A written file, relative to the manifest's directory
+#[derive(Debug, Serialize)]
+pub struct FileEntry {
+    pub path: String,
+    pub bytes: usize,
+    /// Lowercase hex SHA-256 of the file contents
+    pub sha256: String,
+}
+
+impl FileEntry {
+    pub fn new(path: &str, contents: &[u8]) -> Self {
+        Self {
+            path: path.to_string(),
+            bytes: contents.len(),
+            sha256: Sha256::digest(contents)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_entry_checksums_contents() {
+        let entry = FileEntry::new("ec2_us-east-1_all.json", b"abc");
+        assert_eq!(entry.bytes, 3);
+        assert_eq!(
+            entry.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
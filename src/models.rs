@@ -1,5 +1,8 @@
 // Data models for configuration and resources
-use serde::Serialize;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::aws::error::{AwsError, AwsErrorKind};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct ResourceCollection {
@@ -8,12 +11,151 @@ pub struct ResourceCollection {
     pub resource_type: String,
     pub resources: serde_json::Value,
     pub collected_at: String,
+    /// Commands that failed while collecting this resource type
+    pub errors: Vec<CollectionError>,
+}
+
+impl ResourceCollection {
+    pub fn new(
+        service: &str,
+        region: &str,
+        resource_type: &str,
+        resources: serde_json::Value,
+        collected_at: &str,
+    ) -> Self {
+        Self {
+            service: service.to_string(),
+            region: region.to_string(),
+            resource_type: resource_type.to_string(),
+            resources,
+            collected_at: collected_at.to_string(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Wrap the result of the command listing a resource type
+    ///
+    /// A failed command yields a collection without resources that records the
+    /// error; a command reporting that the resource does not exist yields `null`.
+    pub fn from_result(
+        service: &str,
+        region: &str,
+        resource_type: &str,
+        args: &[&str],
+        result: Result<serde_json::Value>,
+        collected_at: &str,
+    ) -> Self {
+        match result {
+            Ok(resources) => Self::new(service, region, resource_type, resources, collected_at),
+            Err(e) => Self::new(
+                service,
+                region,
+                resource_type,
+                serde_json::Value::Null,
+                collected_at,
+            )
+            .with_errors(
+                CollectionError::from_failure(args, &e)
+                    .into_iter()
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Attach errors of commands that failed while collecting this resource type
+    pub fn with_errors(mut self, errors: Vec<CollectionError>) -> Self {
+        self.errors.extend(errors);
+        self
+    }
+
+    /// Whether the resource type could not be collected at all
+    pub fn is_failed(&self) -> bool {
+        self.resources.is_null() && !self.errors.is_empty()
+    }
+}
+
+/// A service, role or profile that could not be collected at all
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionFailure {
+    /// What failed, e.g. `sqs (us-east-1)` or `assume-role {arn}`
+    pub label: String,
+    pub kind: AwsErrorKind,
+}
+
+impl CollectionFailure {
+    pub fn new(label: impl Into<String>, kind: AwsErrorKind) -> Self {
+        Self {
+            label: label.into(),
+            kind,
+        }
+    }
+}
+
+/// A command that failed during collection
+#[derive(Debug, Serialize, Clone)]
+pub struct CollectionError {
+    /// AWS CLI arguments of the failed command
+    pub command: String,
+    pub error_class: AwsErrorKind,
+    pub error_code: Option<String>,
+    pub message: String,
+}
+
+impl CollectionError {
+    /// Describe a failed command, or `None` if the failure only means that the
+    /// requested resource or configuration does not exist
+    pub fn from_failure(args: &[&str], error: &anyhow::Error) -> Option<Self> {
+        let (error_class, error_code, message) = match error.downcast_ref::<AwsError>() {
+            Some(e) => (e.kind, e.code.clone(), e.message.clone()),
+            None => (AwsErrorKind::Unknown, None, format!("{:#}", error)),
+        };
+
+        if error_class == AwsErrorKind::NotFound {
+            return None;
+        }
+
+        Some(Self {
+            command: args.join(" "),
+            error_class,
+            error_code,
+            message,
+        })
+    }
 }
 
 #[derive(Debug, Serialize)]
 pub struct Metadata {
     pub generated_at: String,
+    /// Version of cloud_collector that wrote the output
+    pub tool_version: String,
+    /// Command line of the run
+    pub arguments: Vec<String>,
     pub aws_profile: String,
+    pub account_id: String,
+    /// Partition of the account, e.g. `aws` or `aws-cn`
+    pub partition: String,
+    /// Role assumed to collect the account, if any
+    pub role_arn: Option<String>,
     pub regions: Vec<String>,
     pub services: Vec<String>,
 }
+
+/// A collected resource in a service-independent shape
+///
+/// Produced from the raw CLI payload by the extractor of its resource type; the
+/// payload itself is kept in `raw`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Resource {
+    pub arn: Option<String>,
+    /// Identifier the service uses for the resource, e.g. `i-0abc` or a bucket name
+    pub id: String,
+    pub name: Option<String>,
+    pub service: String,
+    pub resource_type: String,
+    pub region: String,
+    pub account: String,
+    pub tags: std::collections::BTreeMap<String, String>,
+    /// Creation time (RFC 3339 as reported by the service), if the payload has one
+    pub created_at: Option<String>,
+    pub raw: serde_json::Value,
+}
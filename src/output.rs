@@ -1,36 +1,100 @@
 // Output formatter and file writer
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
-use crate::models::{Metadata, ResourceCollection};
+use crate::aws::graph::Graph;
+use crate::aws::normalize;
+use crate::manifest::{CollectionEntry, FileEntry, Manifest};
+use crate::models::{Metadata, Resource, ResourceCollection};
 
+/// Collector wall-clock time in seconds, by service and region
+pub type Durations = HashMap<(String, String), f64>;
+
+/// Write the collections of one account
+///
+/// Files go to `{output_dir}/{profile}/`, or `{output_dir}/{profile}/{account}/`
+/// when `per_account` is set (multi-account runs). Each raw
+/// `{service}_{region}_all.json` file is accompanied by a
+/// `{service}_{region}_resources.json` file of normalized resources, and
+/// `graph.json` holds the relationships between all resources of the account.
+/// `manifest.json` lists every file written with its checksum.
 pub fn write_output(
     collections: Vec<ResourceCollection>,
     output_dir: &str,
     create_new_file: bool,
-    _metadata: Metadata,
-    profile: &str,
+    metadata: Metadata,
+    durations: &Durations,
+    per_account: bool,
 ) -> Result<()> {
-    // Create output directory structure: output/{profile}/
-    let profile_dir = format!("{}/{}", output_dir, profile);
-    fs::create_dir_all(&profile_dir).context(format!(
-        "Failed to create profile directory: {}",
-        profile_dir
-    ))?;
+    // Create output directory structure: output/{profile}/[{account}/]
+    let mut target_dir = format!("{}/{}", output_dir, metadata.aws_profile);
+    if per_account {
+        target_dir = format!("{}/{}", target_dir, metadata.account_id);
+    }
+    fs::create_dir_all(&target_dir)
+        .context(format!("Failed to create output directory: {}", target_dir))?;
+
+    let (resources, entries) = write_files(
+        &collections,
+        &target_dir,
+        create_new_file,
+        &metadata.account_id,
+        &metadata.partition,
+        durations,
+    )?;
+
+    let suffix = if create_new_file {
+        format!("_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S"))
+    } else {
+        String::new()
+    };
+
+    let graph = Graph::build(resources);
+    let json =
+        serde_json::to_string_pretty(&graph.to_json()).context("Failed to serialize graph")?;
+    let graph_file = write_file(&target_dir, &format!("graph{}.json", suffix), json)?;
+    println!(
+        "✓ Wrote: {}/{} ({} resources, {} relationships)",
+        target_dir,
+        graph_file.path,
+        graph.nodes.len(),
+        graph.edges.len()
+    );
 
-    write_files(&collections, &profile_dir, create_new_file)?;
+    let manifest = Manifest {
+        metadata,
+        collections: entries,
+        graph: graph_file,
+    };
+    let json = serde_json::to_string_pretty(&manifest).context("Failed to serialize manifest")?;
+    let manifest_file = write_file(&target_dir, &format!("manifest{}.json", suffix), json)?;
+    println!(
+        "✓ Wrote: {}/{} ({} files)",
+        target_dir,
+        manifest_file.path,
+        manifest.collections.len() * 2 + 1
+    );
 
     Ok(())
 }
 
+/// Write `contents` to `{dir}/{name}` and describe the file for the manifest
+fn write_file(dir: &str, name: &str, contents: String) -> Result<FileEntry> {
+    let filepath = Path::new(dir).join(name);
+    fs::write(&filepath, &contents).context(format!("Failed to write file: {:?}", filepath))?;
+    Ok(FileEntry::new(name, contents.as_bytes()))
+}
+
 fn write_files(
     collections: &[ResourceCollection],
     output_dir: &str,
     create_new_file: bool,
-) -> Result<()> {
-    use std::collections::HashMap;
-
+    account: &str,
+    partition: &str,
+    durations: &Durations,
+) -> Result<(Vec<Resource>, Vec<CollectionEntry>)> {
     // Group collections by service and region
     let mut grouped: HashMap<(String, String), Vec<&ResourceCollection>> = HashMap::new();
 
@@ -40,47 +104,160 @@ fn write_files(
     }
 
     // Write grouped files - each service gets one combined file per region
+    let mut all_resources = Vec::new();
+    let mut entries = Vec::new();
     for ((service, region), group) in grouped {
-        let filename = if create_new_file {
-            format!(
-                "{}_{}_all_{}.json",
-                service,
-                region,
-                chrono::Utc::now().format("%Y%m%d_%H%M%S")
-            )
+        let suffix = if create_new_file {
+            format!("_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S"))
         } else {
-            format!("{}_{}_all.json", service, region)
+            String::new()
         };
 
-        let filepath = Path::new(output_dir).join(filename);
-
-        // Create a combined structure with all resource types
+        // Create a combined structure with all resource types; resource types
+        // that could not be collected at all only appear in `errors`
         let mut combined_resources = serde_json::Map::new();
+        let mut errors = Vec::new();
         for collection in &group {
-            combined_resources.insert(
-                collection.resource_type.clone(),
-                collection.resources.clone(),
-            );
+            if !collection.is_failed() {
+                combined_resources.insert(
+                    collection.resource_type.clone(),
+                    collection.resources.clone(),
+                );
+            }
+            for error in &collection.errors {
+                let mut entry = serde_json::to_value(error)?;
+                entry["resource_type"] = collection.resource_type.clone().into();
+                errors.push(entry);
+            }
         }
 
         let combined = serde_json::json!({
             "service": service,
             "region": region,
             "resources": combined_resources,
+            "errors": errors,
             "collected_at": group[0].collected_at
         });
 
         let json = serde_json::to_string_pretty(&combined)
             .context("Failed to serialize combined collection")?;
 
-        fs::write(&filepath, json).context(format!("Failed to write file: {:?}", filepath))?;
+        let raw = write_file(
+            output_dir,
+            &format!("{}_{}_all{}.json", service, region, suffix),
+            json,
+        )?;
 
-        println!(
-            "✓ Wrote: {} ({} resource types)",
-            filepath.display(),
-            group.len()
-        );
+        if errors.is_empty() {
+            println!(
+                "✓ Wrote: {}/{} ({} resource types)",
+                output_dir,
+                raw.path,
+                combined_resources.len()
+            );
+        } else {
+            println!(
+                "⚠ Wrote: {}/{} ({} resource types, {} errors)",
+                output_dir,
+                raw.path,
+                combined_resources.len(),
+                errors.len()
+            );
+        }
+
+        // Normalized resources of every resource type of the group
+        let resources: Vec<Resource> = group
+            .iter()
+            .flat_map(|collection| normalize::normalize(collection, account, partition))
+            .collect();
+        let normalized = serde_json::json!({
+            "service": service,
+            "region": region,
+            "account": account,
+            "resources": resources,
+            "collected_at": group[0].collected_at
+        });
+
+        let json = serde_json::to_string_pretty(&normalized)
+            .context("Failed to serialize normalized resources")?;
+        let normalized = write_file(
+            output_dir,
+            &format!("{}_{}_resources{}.json", service, region, suffix),
+            json,
+        )?;
+
+        entries.push(CollectionEntry {
+            duration_secs: durations.get(&(service.clone(), region.clone())).copied(),
+            service,
+            region,
+            resource_types: combined_resources.len(),
+            resources: resources.len(),
+            errors: errors.len(),
+            raw,
+            normalized,
+        });
+        all_resources.extend(resources);
     }
 
-    Ok(())
+    // Grouping is unordered; keep the manifest stable between runs
+    entries.sort_by(|a, b| (&a.service, &a.region).cmp(&(&b.service, &b.region)));
+
+    Ok((all_resources, entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn test_manifest_lists_written_files_with_checksums() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_dir = dir.path().to_str().unwrap();
+        let collections = vec![ResourceCollection::new(
+            "ec2",
+            "us-east-1",
+            "instances",
+            json!({ "Reservations": [{ "Instances": [{ "InstanceId": "i-0abc" }] }] }),
+            "now",
+        )];
+        let metadata = Metadata {
+            generated_at: "now".to_string(),
+            tool_version: "1.0.0".to_string(),
+            arguments: vec!["cloud_collector".to_string(), "aws".to_string()],
+            aws_profile: "default".to_string(),
+            account_id: "123456789012".to_string(),
+            partition: "aws".to_string(),
+            role_arn: None,
+            regions: vec!["us-east-1".to_string()],
+            services: vec!["ec2".to_string()],
+        };
+        let durations = Durations::from([(("ec2".to_string(), "us-east-1".to_string()), 1.5)]);
+
+        write_output(collections, output_dir, false, metadata, &durations, false).unwrap();
+
+        let target_dir = dir.path().join("default");
+        let manifest: Value =
+            serde_json::from_str(&fs::read_to_string(target_dir.join("manifest.json")).unwrap())
+                .unwrap();
+        assert_eq!(manifest["account_id"], "123456789012");
+        assert_eq!(manifest["tool_version"], "1.0.0");
+
+        let entry = &manifest["collections"][0];
+        assert_eq!(entry["service"], "ec2");
+        assert_eq!(entry["duration_secs"], 1.5);
+        assert_eq!(entry["resources"], 1);
+        assert_eq!(entry["errors"], 0);
+
+        for file in [&entry["raw"], &entry["normalized"], &manifest["graph"]] {
+            let contents = fs::read(target_dir.join(file["path"].as_str().unwrap())).unwrap();
+            let sha256: String = Sha256::digest(&contents)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            assert_eq!(file["sha256"], sha256.as_str());
+            assert_eq!(file["bytes"], contents.len());
+        }
+    }
 }
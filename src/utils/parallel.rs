@@ -44,6 +44,101 @@ where
         .await
 }
 
+/// Concurrency limit adjusted by additive increase, multiplicative decrease
+///
+/// Every operation holds an [`AimdPermit`] while it runs. Successful operations
+/// grow the limit by about one per limit's worth of completions; an overloaded
+/// operation (e.g. a throttled request) halves it. Operations started before
+/// the last decrease cannot decrease it again, so a burst of failures from one
+/// window only halves the limit once.
+pub struct AimdLimit {
+    state: std::sync::Mutex<AimdState>,
+    changed: tokio::sync::Notify,
+    min: f64,
+    max: f64,
+}
+
+struct AimdState {
+    limit: f64,
+    in_flight: usize,
+    /// Incremented on every decrease
+    epoch: u64,
+}
+
+impl AimdLimit {
+    pub fn new(initial: usize, min: usize, max: usize) -> Self {
+        Self {
+            state: std::sync::Mutex::new(AimdState {
+                limit: initial.clamp(min, max) as f64,
+                in_flight: 0,
+                epoch: 0,
+            }),
+            changed: tokio::sync::Notify::new(),
+            min: min.max(1) as f64,
+            max: max as f64,
+        }
+    }
+
+    /// Wait until another operation may start
+    pub async fn acquire(self: &std::sync::Arc<Self>) -> AimdPermit {
+        loop {
+            // Registered before checking, so a release in between is not missed
+            let changed = self.changed.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.in_flight < state.limit as usize {
+                    state.in_flight += 1;
+                    return AimdPermit {
+                        limit: std::sync::Arc::clone(self),
+                        epoch: state.epoch,
+                    };
+                }
+            }
+            changed.await;
+        }
+    }
+
+    /// Current number of operations allowed in flight
+    pub fn current(&self) -> usize {
+        self.state.lock().unwrap().limit as usize
+    }
+
+    /// Number of times the limit was decreased
+    pub fn decreases(&self) -> u64 {
+        self.state.lock().unwrap().epoch
+    }
+}
+
+/// Slot of a running operation; released when dropped
+pub struct AimdPermit {
+    limit: std::sync::Arc<AimdLimit>,
+    epoch: u64,
+}
+
+impl AimdPermit {
+    /// The operation succeeded: grow the limit
+    pub fn success(self) {
+        let mut state = self.limit.state.lock().unwrap();
+        state.limit = (state.limit + 1.0 / state.limit).min(self.limit.max);
+    }
+
+    /// The operation was rejected for overload: halve the limit
+    pub fn overloaded(self) {
+        let mut state = self.limit.state.lock().unwrap();
+        if state.epoch == self.epoch {
+            state.limit = (state.limit / 2.0).max(self.limit.min);
+            state.epoch += 1;
+        }
+    }
+}
+
+impl Drop for AimdPermit {
+    fn drop(&mut self) {
+        self.limit.state.lock().unwrap().in_flight -= 1;
+        self.limit.changed.notify_waiters();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -57,4 +152,29 @@ mod tests {
         assert!(results.contains(&2));
         assert!(results.contains(&10));
     }
+
+    #[tokio::test]
+    async fn test_aimd_limit_grows_on_success_and_halves_once_per_window() {
+        let limit = std::sync::Arc::new(AimdLimit::new(4, 1, 8));
+
+        // About a window of successes grows the limit by one
+        for _ in 0..5 {
+            limit.acquire().await.success();
+        }
+        assert_eq!(limit.current(), 5);
+
+        // Concurrent throttling from the same window halves it only once
+        let first = limit.acquire().await;
+        let second = limit.acquire().await;
+        first.overloaded();
+        second.overloaded();
+        assert_eq!(limit.current(), 2);
+        assert_eq!(limit.decreases(), 1);
+
+        // Never below the minimum
+        for _ in 0..5 {
+            limit.acquire().await.overloaded();
+        }
+        assert_eq!(limit.current(), 1);
+    }
 }